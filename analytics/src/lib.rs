@@ -0,0 +1,116 @@
+//! Clustering metrics over loose food positions, shared by the Bevy sim and
+//! the backend so both report the same numbers for the same food layout.
+
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FoodPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClusteringSummary {
+    pub loose_food_count: usize,
+    /// Grid cells of the given size containing at least one loose food item
+    pub occupied_cell_count: usize,
+    /// Mean distance from each loose food item to its nearest neighbour
+    pub nearest_neighbor_mean_distance: f64,
+}
+
+impl ClusteringSummary {
+    pub fn from_positions(positions: &[FoodPosition], cell_size: f64) -> Self {
+        Self {
+            loose_food_count: positions.len(),
+            occupied_cell_count: occupied_cell_count(positions, cell_size),
+            nearest_neighbor_mean_distance: mean_nearest_neighbor_distance(positions),
+        }
+    }
+}
+
+/// Number of distinct `cell_size` grid cells the positions fall into.
+/// A non-positive cell size falls back to 1.
+pub fn occupied_cell_count(positions: &[FoodPosition], cell_size: f64) -> usize {
+    let effective_cell_size = if cell_size > 0.0 { cell_size } else { 1.0 };
+    positions
+        .iter()
+        .map(|position| {
+            (
+                (position.x / effective_cell_size).floor() as i64,
+                (position.y / effective_cell_size).floor() as i64,
+            )
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Mean over all positions of the distance to the nearest other position.
+/// 0 with fewer than two positions.
+pub fn mean_nearest_neighbor_distance(positions: &[FoodPosition]) -> f64 {
+    if positions.len() < 2 {
+        return 0.0;
+    }
+
+    let total = positions
+        .iter()
+        .enumerate()
+        .map(|(index, position)| {
+            positions
+                .iter()
+                .enumerate()
+                .filter_map(|(other_index, other)| {
+                    if index == other_index {
+                        return None;
+                    }
+                    Some(((position.x - other.x).powi(2) + (position.y - other.y).powi(2)).sqrt())
+                })
+                .fold(f64::MAX, f64::min)
+        })
+        .sum::<f64>();
+
+    total / positions.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f64, y: f64) -> FoodPosition {
+        FoodPosition { x, y }
+    }
+
+    #[test]
+    fn counts_distinct_cells() {
+        let positions = [at(0.0, 0.0), at(49.0, 49.0), at(50.0, 0.0), at(-1.0, 0.0)];
+        assert_eq!(occupied_cell_count(&positions, 50.0), 3);
+        assert_eq!(occupied_cell_count(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn non_positive_cell_size_falls_back_to_one() {
+        let positions = [at(0.5, 0.5), at(1.5, 0.5)];
+        assert_eq!(occupied_cell_count(&positions, 0.0), 2);
+    }
+
+    #[test]
+    fn mean_nearest_neighbor_distance_averages_each_items_nearest() {
+        let positions = [at(0.0, 0.0), at(3.0, 4.0), at(100.0, 0.0)];
+        let expected = (5.0 + 5.0 + (97.0f64 * 97.0 + 16.0).sqrt()) / 3.0;
+        assert!((mean_nearest_neighbor_distance(&positions) - expected).abs() < 1e-9);
+        assert_eq!(mean_nearest_neighbor_distance(&[at(1.0, 1.0)]), 0.0);
+    }
+
+    #[test]
+    fn summary_combines_the_metrics() {
+        let positions = [at(0.0, 0.0), at(3.0, 4.0)];
+        let summary = ClusteringSummary::from_positions(&positions, 50.0);
+        assert_eq!(
+            summary,
+            ClusteringSummary {
+                loose_food_count: 2,
+                occupied_cell_count: 1,
+                nearest_neighbor_mean_distance: 5.0,
+            }
+        );
+    }
+}
@@ -1,8 +1,11 @@
-use std::{collections::HashMap, sync::{
-    Arc, Once, RwLock,
-    atomic::{AtomicBool, Ordering},
-}};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        Arc, Once, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use axum::{
     Json, Router,
@@ -10,25 +13,32 @@ use axum::{
         Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
-    http::header,
+    http::{StatusCode, header},
     response::{Html, IntoResponse},
-    routing::get,
+    routing::{get, post},
 };
 use futures_util::StreamExt;
 use serde::Deserialize;
 use tokio::net::TcpListener;
-use tokio::sync::{Mutex, Notify, broadcast};
+use tokio::sync::{Mutex, Notify, broadcast, mpsc};
 
 use crate::{
     dashboard::{dashboard_css, dashboard_js, normalize_snapshot, render_dashboard},
-    protocol::{EventEnvelope, EventPayload},
+    protocol::{CommandEnvelope, EventEnvelope, EventPayload, SimCommand},
     store::{Registry, SimHandle},
     summary::{
-        AnalyticsMetaResponse, CachedAnalyticsSnapshot, CachedLiveSnapshot, CachedSnapshot,
-        BreakpointTotalsResponse, SimSummaryResponse, SummaryResponse,
+        AnalyticsMetaResponse, BreakpointTotalsResponse, CachedAnalyticsSnapshot,
+        CachedLiveSnapshot, CachedSnapshot, SimCommandResponse, SimDepartureResponse,
+        SimSummaryResponse, SummaryResponse,
     },
 };
 
+/// Number of `sim_goodbye` departures kept for `AppState::recent_departures`.
+const MAX_RECENT_DEPARTURES: usize = 32;
+
+/// Commands buffered per sim before `send_command` skips it.
+const COMMAND_CHANNEL_CAPACITY: usize = 16;
+
 #[derive(Clone)]
 pub struct AppState {
     inner: Arc<AppStateInner>,
@@ -46,6 +56,8 @@ struct AppStateInner {
     worker_once: Once,
     tuning: RefreshTuning,
     dashboard_tx: broadcast::Sender<CachedSnapshot>,
+    recent_departures: RwLock<VecDeque<SimDepartureResponse>>,
+    command_senders: RwLock<HashMap<String, mpsc::Sender<SimCommand>>>,
 }
 
 struct RefreshState {
@@ -72,6 +84,32 @@ struct BreakpointTotalsQuery {
     prefix: String,
 }
 
+/// Body of `POST /api/sims/command`: exactly one of `sim_id` or `prefix`.
+#[derive(Clone, Debug, Deserialize)]
+struct SimCommandRequest {
+    #[serde(default)]
+    sim_id: Option<String>,
+    #[serde(default)]
+    prefix: Option<String>,
+    command: SimCommand,
+}
+
+/// Which connected sims a command goes to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandTarget {
+    Sim(String),
+    Prefix(String),
+}
+
+impl CommandTarget {
+    fn matches(&self, sim_id: &str) -> bool {
+        match self {
+            CommandTarget::Sim(target) => sim_id == target,
+            CommandTarget::Prefix(prefix) => sim_id.starts_with(prefix.as_str()),
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 struct RefreshTuning {
     min_refresh_interval: Duration,
@@ -121,6 +159,8 @@ impl AppState {
                 worker_once: Once::new(),
                 tuning,
                 dashboard_tx,
+                recent_departures: RwLock::new(VecDeque::new()),
+                command_senders: RwLock::new(HashMap::new()),
             }),
         }
     }
@@ -128,10 +168,23 @@ impl AppState {
     /// Convenience entry point used by tests and `AppState::apply_event`.
     /// Hot-path ingest bypasses the registry lookup via `apply_event_with_handle`.
     pub fn apply_event(&self, envelope: EventEnvelope) -> Result<(), String> {
-        let handle = self.inner.registry.get_or_create(&envelope.sim_id);
+        let handle = if matches!(envelope.payload, EventPayload::SimHello(_)) {
+            self.start_session(&envelope.sim_id)
+        } else {
+            self.inner.registry.get_or_create(&envelope.sim_id)
+        };
         self.apply_event_with_handle(&handle, envelope)
     }
 
+    /// A `sim_hello` starts the sim over, e.g. after a reset: the sim's old
+    /// handle and counts are dropped and a fresh handle takes its place.
+    fn start_session(&self, sim_id: &str) -> Arc<SimHandle> {
+        if let Some(previous) = self.inner.registry.get(sim_id) {
+            self.remove_sim_handle(&previous);
+        }
+        self.inner.registry.get_or_create(sim_id)
+    }
+
     /// Apply an event using a pre-cached sim handle.  No registry access.
     pub(crate) fn apply_event_with_handle(
         &self,
@@ -146,6 +199,11 @@ impl AppState {
         }
         let analytics_affected = event_affects_analytics(&envelope);
         let outcome = handle.apply_event(&envelope)?;
+        if let EventPayload::SimGoodbye(payload) = &envelope.payload {
+            self.record_departure(&envelope.sim_id, &payload.reason);
+            self.remove_sim_handle(handle);
+            return Ok(());
+        }
         self.apply_live_event(&envelope, &outcome);
 
         if self.inner.dashboard_tx.receiver_count() > 0 {
@@ -219,6 +277,79 @@ impl AppState {
         }
     }
 
+    /// The latest sims that said `sim_goodbye`, oldest first.
+    pub fn recent_departures(&self) -> Vec<SimDepartureResponse> {
+        self.inner
+            .recent_departures
+            .read()
+            .expect("departures lock poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    fn record_departure(&self, sim_id: &str, reason: &str) {
+        let mut departures = self
+            .inner
+            .recent_departures
+            .write()
+            .expect("departures lock poisoned");
+        if departures.len() == MAX_RECENT_DEPARTURES {
+            departures.pop_front();
+        }
+        departures.push_back(SimDepartureResponse {
+            sim_id: sim_id.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Queues `command` for every connected sim `target` matches and returns
+    /// their ids, sorted. Sims whose command buffer is full are skipped.
+    pub fn send_command(&self, target: &CommandTarget, command: &SimCommand) -> Vec<String> {
+        let senders = self
+            .inner
+            .command_senders
+            .read()
+            .expect("command senders lock poisoned");
+        let mut sent_to: Vec<String> = senders
+            .iter()
+            .filter(|(sim_id, _)| target.matches(sim_id))
+            .filter(|(_, sender)| sender.try_send(command.clone()).is_ok())
+            .map(|(sim_id, _)| sim_id.clone())
+            .collect();
+        sent_to.sort();
+        sent_to
+    }
+
+    /// Routes commands for `sim_id` to the returned receiver, replacing any
+    /// earlier connection of the same sim.
+    fn open_command_channel(
+        &self,
+        sim_id: &str,
+    ) -> (mpsc::Sender<SimCommand>, mpsc::Receiver<SimCommand>) {
+        let (sender, receiver) = mpsc::channel(COMMAND_CHANNEL_CAPACITY);
+        self.inner
+            .command_senders
+            .write()
+            .expect("command senders lock poisoned")
+            .insert(sim_id.to_string(), sender.clone());
+        (sender, receiver)
+    }
+
+    fn close_command_channel(&self, sim_id: &str, sender: &mpsc::Sender<SimCommand>) {
+        let mut senders = self
+            .inner
+            .command_senders
+            .write()
+            .expect("command senders lock poisoned");
+        if senders
+            .get(sim_id)
+            .is_some_and(|current| current.same_channel(sender))
+        {
+            senders.remove(sim_id);
+        }
+    }
+
     fn current_sims(&self, limit: usize) -> Vec<SimSummaryResponse> {
         self.inner
             .live_snapshot
@@ -514,6 +645,8 @@ fn event_affects_analytics(envelope: &EventEnvelope) -> bool {
         EventPayload::SimFoodSnapshot(_)
             | EventPayload::FoodPickup(_)
             | EventPayload::FoodDrop(_)
+            | EventPayload::FoodSpawn(_)
+            | EventPayload::FoodRemove(_)
     )
 }
 
@@ -552,6 +685,7 @@ pub fn build_router_with_state(state: AppState) -> Router {
         .route("/api/summary", get(summary))
         .route("/api/sims", get(sims))
         .route("/api/breakpoint_totals", get(breakpoint_totals))
+        .route("/api/sims/command", post(sim_command))
         .route("/ws/ingest", get(ingest_ws))
         .route("/ws/dashboard", get(dashboard_ws))
         .route("/", get(dashboard))
@@ -589,6 +723,33 @@ async fn breakpoint_totals(
     Json(state.current_breakpoint_totals(&query.prefix))
 }
 
+async fn sim_command(
+    State(state): State<AppState>,
+    Json(request): Json<SimCommandRequest>,
+) -> Result<Json<SimCommandResponse>, (StatusCode, String)> {
+    let target = match (request.sim_id, request.prefix) {
+        (Some(sim_id), None) => CommandTarget::Sim(sim_id),
+        (None, Some(prefix)) => CommandTarget::Prefix(prefix),
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "expected exactly one of sim_id or prefix".to_string(),
+            ));
+        }
+    };
+
+    let sent_to = state.send_command(&target, &request.command);
+    if let CommandTarget::Sim(sim_id) = &target
+        && sent_to.is_empty()
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("sim {sim_id} is not connected"),
+        ));
+    }
+    Ok(Json(SimCommandResponse { sent_to }))
+}
+
 async fn dashboard(State(state): State<AppState>) -> Html<String> {
     let _ = state;
     Html(render_dashboard().to_string())
@@ -615,27 +776,63 @@ async fn dashboard_ws(ws: WebSocketUpgrade, State(state): State<AppState>) -> im
 
 /// Ingest WebSocket handler.
 /// The sim handle is looked up once from the registry (on first event) and
-/// cached for the lifetime of the connection.  All subsequent events bypass
-/// the registry entirely.
+/// cached until the next `sim_hello`, which starts a fresh session.  All
+/// other events bypass the registry entirely.  Commands for the sim are
+/// written back on the same socket.
 async fn handle_ingest_socket(state: AppState, mut socket: WebSocket) {
     let mut cached_handle: Option<Arc<SimHandle>> = None;
-    while let Some(message_result) = socket.next().await {
-        let Ok(message) = message_result else {
-            break;
-        };
-        let Message::Text(text) = message else {
-            continue;
-        };
-        let Ok(event) = serde_json::from_str::<EventEnvelope>(&text) else {
-            continue;
-        };
-        let handle = cached_handle
-            .get_or_insert_with(|| state.inner.registry.get_or_create(&event.sim_id));
-        if state.apply_event_with_handle(handle, event).is_err() {
-            break;
+    let mut commands: Option<(mpsc::Sender<SimCommand>, mpsc::Receiver<SimCommand>)> = None;
+    loop {
+        tokio::select! {
+            message_result = socket.next() => {
+                let Some(Ok(message)) = message_result else {
+                    break;
+                };
+                let Message::Text(text) = message else {
+                    continue;
+                };
+                let Ok(event) = serde_json::from_str::<EventEnvelope>(&text) else {
+                    continue;
+                };
+                let hello = matches!(event.payload, EventPayload::SimHello(_));
+                if hello
+                    && cached_handle
+                        .as_ref()
+                        .is_none_or(|handle| handle.sim_id == event.sim_id)
+                {
+                    cached_handle = Some(state.start_session(&event.sim_id));
+                }
+                let handle = cached_handle
+                    .get_or_insert_with(|| state.inner.registry.get_or_create(&event.sim_id));
+                if commands.is_none() {
+                    commands = Some(state.open_command_channel(&event.sim_id));
+                }
+                let goodbye = matches!(event.payload, EventPayload::SimGoodbye(_));
+                if state.apply_event_with_handle(handle, event).is_err() || goodbye {
+                    break;
+                }
+            }
+            Some(command) = async {
+                match commands.as_mut() {
+                    Some((_, receiver)) => receiver.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                let Some(handle) = &cached_handle else {
+                    continue;
+                };
+                let envelope = CommandEnvelope::new(&handle.sim_id, command);
+                let json = serde_json::to_string(&envelope).expect("sim command json");
+                if socket.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
         }
     }
     if let Some(handle) = cached_handle {
+        if let Some((sender, _)) = &commands {
+            state.close_command_channel(&handle.sim_id, sender);
+        }
         state.remove_sim_handle(&handle);
     }
 }
@@ -688,10 +885,12 @@ mod tests {
 
     use serde_json::Value;
 
-    use super::{AppState, RefreshTuning};
+    use super::{AppState, CommandTarget, RefreshTuning};
     use crate::protocol::{
-        EventEnvelope, EventPayload, FoodSnapshotPayload, HelloPayload, StartupFoodPayload,
+        EventEnvelope, EventPayload, FoodSnapshotPayload, GoodbyePayload, HelloPayload, SimCommand,
+        StartupFoodPayload,
     };
+    use crate::summary::SimDepartureResponse;
 
     // ---- helpers ----
 
@@ -720,6 +919,7 @@ mod tests {
             seq: 2,
             timestamp_ms: 0,
             payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                slot_count: None,
                 foods: (0..count)
                     .map(|i| StartupFoodPayload {
                         food_id: i,
@@ -739,6 +939,7 @@ mod tests {
             timestamp_ms: 0,
             payload: EventPayload::FoodPickup(crate::protocol::FoodPickupPayload {
                 ant_id: Some("ant-1".into()),
+                colony_id: None,
                 food_id: slot,
                 x: None,
                 y: None,
@@ -757,6 +958,7 @@ mod tests {
             timestamp_ms: 0,
             payload: EventPayload::FoodDrop(crate::protocol::FoodDropPayload {
                 ant_id: Some("ant-1".into()),
+                colony_id: None,
                 food_id: slot,
                 x,
                 y,
@@ -847,6 +1049,7 @@ mod tests {
                 timestamp_ms: 0,
                 payload: EventPayload::FoodPickup(crate::protocol::FoodPickupPayload {
                     ant_id: Some("ant-1".into()),
+                    colony_id: None,
                     food_id: usize::MAX,
                     x: Some(50.0),
                     y: Some(50.0),
@@ -911,6 +1114,108 @@ mod tests {
         assert_eq!(sims[0]["sim_id"], "sim-a");
     }
 
+    #[test]
+    fn commands_reach_only_the_targeted_open_channels() {
+        let state = AppState::new();
+        let (_, mut alpha) = state.open_command_channel("batch-a");
+        let (_, mut beta) = state.open_command_channel("batch-b");
+        let (other_sender, mut other) = state.open_command_channel("solo");
+
+        assert_eq!(
+            state.send_command(&CommandTarget::Prefix("batch-".into()), &SimCommand::Pause),
+            vec!["batch-a".to_string(), "batch-b".to_string()]
+        );
+        assert_eq!(
+            state.send_command(
+                &CommandTarget::Sim("solo".into()),
+                &SimCommand::SetSpeed { speed: 2.0 }
+            ),
+            vec!["solo".to_string()]
+        );
+        assert_eq!(alpha.try_recv(), Ok(SimCommand::Pause));
+        assert_eq!(beta.try_recv(), Ok(SimCommand::Pause));
+        assert_eq!(other.try_recv(), Ok(SimCommand::SetSpeed { speed: 2.0 }));
+        assert!(alpha.try_recv().is_err() && other.try_recv().is_err());
+
+        state.close_command_channel("solo", &other_sender);
+        assert!(
+            state
+                .send_command(&CommandTarget::Sim("solo".into()), &SimCommand::Resume)
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn sim_goodbye_removes_the_sim_and_records_the_reason() {
+        let state = AppState::new();
+        let sid = "sim-goodbye";
+        state
+            .apply_event(sim_hello_envelope(sid))
+            .expect("sim hello");
+        state
+            .apply_event(sim_food_snapshot_envelope(sid, 2))
+            .expect("snapshot");
+
+        state
+            .apply_event(EventEnvelope {
+                event_type: "sim_goodbye".into(),
+                sim_id: sid.into(),
+                seq: 3,
+                timestamp_ms: 0,
+                payload: EventPayload::SimGoodbye(GoodbyePayload {
+                    reason: "shutdown".into(),
+                }),
+            })
+            .expect("sim_goodbye should be accepted");
+
+        let snapshot = current_snapshot_json(&state);
+        assert_eq!(
+            snapshot["summary"]["live_summary"]["connected_sim_count"],
+            0
+        );
+        assert_eq!(snapshot["summary"]["live_summary"]["loose_food_count"], 0);
+        assert!(snapshot["sims"].as_array().expect("sims array").is_empty());
+        assert_eq!(
+            state.recent_departures(),
+            vec![SimDepartureResponse {
+                sim_id: sid.into(),
+                reason: "shutdown".into(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn sim_hello_starts_a_fresh_session_for_a_reset_sim() {
+        let state = AppState::new();
+        let sid = "sim-reset";
+        state
+            .apply_event(sim_hello_envelope(sid))
+            .expect("sim hello");
+        state
+            .apply_event(sim_food_snapshot_envelope(sid, 3))
+            .expect("snapshot");
+        state
+            .apply_event(food_pickup_envelope(sid, 1, 3))
+            .expect("food_pickup");
+
+        // After a reset the sim's slots start over with a smaller world
+        state
+            .apply_event(sim_hello_envelope(sid))
+            .expect("second sim hello");
+        state
+            .apply_event(sim_food_snapshot_envelope(sid, 2))
+            .expect("snapshot of the reset world should be accepted");
+
+        let snapshot = current_snapshot_json(&state);
+        assert_eq!(
+            snapshot["summary"]["live_summary"]["connected_sim_count"],
+            1
+        );
+        assert_eq!(snapshot["summary"]["live_summary"]["loose_food_count"], 2);
+        assert_eq!(snapshot["sims"][0]["pickup_count"], 0);
+        assert_eq!(snapshot["sims"][0]["loose_food_count"], 2);
+    }
+
     // ---- analytics scheduling tests ----
 
     #[tokio::test]
@@ -1015,6 +1320,7 @@ mod tests {
                 seq: 2,
                 timestamp_ms: 0,
                 payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                    slot_count: None,
                     foods: (0..5000)
                         .map(|index| StartupFoodPayload {
                             food_id: index,
@@ -1149,6 +1455,7 @@ mod tests {
                         seq: 2,
                         timestamp_ms: 0,
                         payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                            slot_count: None,
                             foods: (0..5000)
                                 .map(|index| StartupFoodPayload {
                                     food_id: index,
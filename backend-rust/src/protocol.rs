@@ -14,9 +14,12 @@ pub struct EventEnvelope {
 pub enum EventPayload {
     SimHello(HelloPayload),
     SimFoodSnapshot(FoodSnapshotPayload),
+    SimAntSnapshot(AntSnapshotPayload),
     SimHeartbeat(HeartbeatPayload),
     FoodPickup(FoodPickupPayload),
     FoodDrop(FoodDropPayload),
+    FoodSpawn(FoodSpawnPayload),
+    FoodRemove(FoodRemovePayload),
     AntTurnMove(TurnMovePayload),
     SimGoodbye(GoodbyePayload),
 }
@@ -40,6 +43,10 @@ pub struct HelloPayload {
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FoodSnapshotPayload {
+    /// Number of food slots the sim has handed out; unlisted slots are
+    /// absent.  Defaults to `foods.len()`, a dense startup set.
+    #[serde(default)]
+    pub slot_count: Option<usize>,
     pub foods: Vec<StartupFoodPayload>,
 }
 
@@ -50,6 +57,21 @@ pub struct StartupFoodPayload {
     pub y: f32,
 }
 
+/// The ants present at startup, with their dense spawn-order ids.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AntSnapshotPayload {
+    pub ants: Vec<StartupAntPayload>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StartupAntPayload {
+    pub ant_id: String,
+    #[serde(default)]
+    pub colony_id: u32,
+    pub x: f32,
+    pub y: f32,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HeartbeatPayload {
     pub connected_ant_count: usize,
@@ -61,6 +83,8 @@ pub struct HeartbeatPayload {
 pub struct FoodPickupPayload {
     #[serde(default)]
     pub ant_id: Option<String>,
+    #[serde(default)]
+    pub colony_id: Option<u32>,
     pub food_id: usize,
     #[serde(default)]
     pub x: Option<f32>,
@@ -78,6 +102,8 @@ pub struct FoodPickupPayload {
 pub struct FoodDropPayload {
     #[serde(default)]
     pub ant_id: Option<String>,
+    #[serde(default)]
+    pub colony_id: Option<u32>,
     pub food_id: usize,
     pub x: f32,
     pub y: f32,
@@ -89,9 +115,25 @@ pub struct FoodDropPayload {
     pub frame: Option<u64>,
 }
 
+/// Food placed after the startup snapshot, in the next free slot.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FoodSpawnPayload {
+    pub food_id: usize,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Food deleted from the sim; its slot stays empty.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FoodRemovePayload {
+    pub food_id: usize,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TurnMovePayload {
     pub ant_id: String,
+    #[serde(default)]
+    pub colony_id: Option<u32>,
     pub x: f32,
     pub y: f32,
     pub direction_x: f32,
@@ -104,6 +146,36 @@ pub struct GoodbyePayload {
     pub reason: String,
 }
 
+/// A command from the server to one sim, sent down its ingest socket.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum SimCommand {
+    SetSpeed { speed: f32 },
+    Pause,
+    Resume,
+    Reset { seed: u64 },
+    RequestSnapshot,
+}
+
+/// Wire form of a `SimCommand`: `{"type": "sim_command", "sim_id", "payload"}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommandEnvelope {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub sim_id: String,
+    pub payload: SimCommand,
+}
+
+impl CommandEnvelope {
+    pub fn new(sim_id: &str, command: SimCommand) -> Self {
+        Self {
+            message_type: "sim_command".to_string(),
+            sim_id: sim_id.to_string(),
+            payload: command,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct RawEnvelope {
     #[serde(rename = "type")]
@@ -137,6 +209,9 @@ impl<'de> Deserialize<'de> for EventEnvelope {
             "sim_food_snapshot" => EventPayload::SimFoodSnapshot(
                 serde_json::from_value(raw.payload).map_err(D::Error::custom)?,
             ),
+            "sim_ant_snapshot" => EventPayload::SimAntSnapshot(
+                serde_json::from_value(raw.payload).map_err(D::Error::custom)?,
+            ),
             "sim_heartbeat" => EventPayload::SimHeartbeat(
                 serde_json::from_value(raw.payload).map_err(D::Error::custom)?,
             ),
@@ -146,6 +221,12 @@ impl<'de> Deserialize<'de> for EventEnvelope {
             "food_drop" => EventPayload::FoodDrop(
                 serde_json::from_value(raw.payload).map_err(D::Error::custom)?,
             ),
+            "food_spawn" => EventPayload::FoodSpawn(
+                serde_json::from_value(raw.payload).map_err(D::Error::custom)?,
+            ),
+            "food_remove" => EventPayload::FoodRemove(
+                serde_json::from_value(raw.payload).map_err(D::Error::custom)?,
+            ),
             "ant_turn_move" => EventPayload::AntTurnMove(
                 serde_json::from_value(raw.payload).map_err(D::Error::custom)?,
             ),
@@ -190,9 +271,12 @@ impl Serialize for EventPayload {
         match self {
             EventPayload::SimHello(payload) => payload.serialize(serializer),
             EventPayload::SimFoodSnapshot(payload) => payload.serialize(serializer),
+            EventPayload::SimAntSnapshot(payload) => payload.serialize(serializer),
             EventPayload::SimHeartbeat(payload) => payload.serialize(serializer),
             EventPayload::FoodPickup(payload) => payload.serialize(serializer),
             EventPayload::FoodDrop(payload) => payload.serialize(serializer),
+            EventPayload::FoodSpawn(payload) => payload.serialize(serializer),
+            EventPayload::FoodRemove(payload) => payload.serialize(serializer),
             EventPayload::AntTurnMove(payload) => payload.serialize(serializer),
             EventPayload::SimGoodbye(payload) => payload.serialize(serializer),
         }
@@ -7,6 +7,8 @@ use std::{
     time::Instant,
 };
 
+use gatherers_analytics::ClusteringSummary;
+
 use crate::{
     protocol::{EventEnvelope, EventPayload},
     summary::{AnalyticsSummaryResponse, SimSummaryResponse},
@@ -33,6 +35,10 @@ impl AtomicFoodSlot {
         Self { bits: AtomicU64::new(pack_present(x, y)) }
     }
 
+    fn new(position: Option<(f32, f32)>) -> Self {
+        Self { bits: AtomicU64::new(position.map_or(ABSENT_BITS, |(x, y)| pack_present(x, y))) }
+    }
+
     /// Mark this slot as absent (food picked up).
     fn store_absent(&self) {
         self.bits.store(ABSENT_BITS, Ordering::Relaxed);
@@ -43,6 +49,13 @@ impl AtomicFoodSlot {
         self.bits.store(pack_present(x, y), Ordering::Relaxed);
     }
 
+    fn store(&self, position: Option<(f32, f32)>) {
+        match position {
+            Some((x, y)) => self.store_present(x, y),
+            None => self.store_absent(),
+        }
+    }
+
     /// `Some((x, y))` if present, `None` if absent.
     fn load(&self) -> Option<(f32, f32)> {
         let word = self.bits.load(Ordering::Relaxed);
@@ -77,6 +90,9 @@ pub struct SimHandle {
     /// Fixed-size food slot array, installed exactly once by the first
     /// `sim_food_snapshot` event.  `food_id` is a direct slot index.
     foods: OnceLock<Box<[AtomicFoodSlot]>>,
+    /// Slots for food placed after the snapshot via `food_spawn`, continuing
+    /// the ids after the startup slots.  Only the writer appends to it.
+    spawned_foods: RwLock<Vec<AtomicFoodSlot>>,
     pub ant_count: AtomicUsize,
     pub total_events: AtomicUsize,
     pub pickup_count: AtomicUsize,
@@ -91,6 +107,7 @@ impl SimHandle {
             sim_id,
             connected_at: OnceLock::new(),
             foods: OnceLock::new(),
+            spawned_foods: RwLock::new(Vec::new()),
             ant_count: AtomicUsize::new(0),
             total_events: AtomicUsize::new(0),
             pickup_count: AtomicUsize::new(0),
@@ -105,16 +122,20 @@ impl SimHandle {
         self.total_events.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Replace every known slot with the snapshot.  Slots the snapshot does
+    /// not list are absent (carried or removed); slots past the known ones
+    /// are added as spawned food.
     fn apply_food_snapshot(
         &self,
         payload: &crate::protocol::FoodSnapshotPayload,
     ) -> Result<isize, String> {
-        let count = payload.foods.len();
+        // Older clients send only the dense startup set
+        let count = payload.slot_count.unwrap_or(payload.foods.len());
         let mut positions = vec![None; count];
         for food in &payload.foods {
             if food.food_id >= count {
                 return Err(format!(
-                    "sim_food_snapshot slot id {} out of range for {} foods",
+                    "sim_food_snapshot slot id {} out of range for {} slots",
                     food.food_id, count
                 ));
             }
@@ -126,37 +147,83 @@ impl SimHandle {
             }
             positions[food.food_id] = Some((food.x, food.y));
         }
-        if positions.iter().any(Option::is_none) {
-            return Err("sim_food_snapshot must provide a dense slot set".into());
-        }
+        let loose = positions.iter().flatten().count();
 
-        self.record_event();
-
-        if let Some(existing) = self.foods.get() {
-            if existing.len() != count {
+        if let Some(startup) = self.foods.get() {
+            let mut spawned = self.spawned_foods.write().expect("spawned foods lock poisoned");
+            let known = startup.len() + spawned.len();
+            if count < known {
                 return Err(format!(
-                    "sim_food_snapshot shape mismatch: expected {} foods, got {}",
-                    existing.len(), count
+                    "sim_food_snapshot shape mismatch: expected at least {} slots, got {}",
+                    known, count
                 ));
             }
-            for (slot, position) in existing.iter().zip(positions.into_iter()) {
-                let (x, y) = position.expect("validated dense slot set");
-                slot.store_present(x, y);
+            self.record_event();
+            let (known_positions, new_positions) = positions.split_at(known);
+            for (slot, position) in startup.iter().chain(spawned.iter()).zip(known_positions) {
+                slot.store(*position);
             }
+            spawned.extend(new_positions.iter().map(|position| AtomicFoodSlot::new(*position)));
         } else {
-            let slots: Box<[AtomicFoodSlot]> = positions
-                .into_iter()
-                .map(|position| {
-                    let (x, y) = position.expect("validated dense slot set");
-                    AtomicFoodSlot::new_present(x, y)
-                })
-                .collect::<Vec<_>>()
-                .into_boxed_slice();
+            self.record_event();
+            let slots: Box<[AtomicFoodSlot]> =
+                positions.into_iter().map(AtomicFoodSlot::new).collect();
             let _ = self.foods.set(slots);
         }
 
-        let prev = self.loose_food_count.swap(count, Ordering::Relaxed);
-        Ok(count as isize - prev as isize)
+        let prev = self.loose_food_count.swap(loose, Ordering::Relaxed);
+        Ok(loose as isize - prev as isize)
+    }
+
+    /// Run `f` on the slot for `food_id`, startup or spawned.
+    fn with_food_slot<R>(&self, food_id: usize, f: impl FnOnce(&AtomicFoodSlot) -> R) -> Option<R> {
+        let startup = self.foods.get()?;
+        if let Some(slot) = startup.get(food_id) {
+            return Some(f(slot));
+        }
+        let spawned = self.spawned_foods.read().expect("spawned foods lock poisoned");
+        spawned.get(food_id - startup.len()).map(f)
+    }
+
+    /// Mark `slot` present at `(x, y)`, counting it if it was absent.
+    fn place_food(&self, slot: &AtomicFoodSlot, x: f32, y: f32) -> isize {
+        let was_absent = !slot.is_present();
+        slot.store_present(x, y);
+        if was_absent {
+            self.loose_food_count.fetch_add(1, Ordering::Relaxed);
+            1
+        } else {
+            0
+        }
+    }
+
+    fn apply_food_spawn(
+        &self,
+        payload: &crate::protocol::FoodSpawnPayload,
+    ) -> Result<isize, String> {
+        let Some(startup) = self.foods.get() else {
+            return Err("food_spawn before sim_food_snapshot".into());
+        };
+        self.record_event();
+
+        // Re-announcing a known slot just places it again
+        if let Some(delta) =
+            self.with_food_slot(payload.food_id, |slot| self.place_food(slot, payload.x, payload.y))
+        {
+            return Ok(delta);
+        }
+
+        let mut spawned = self.spawned_foods.write().expect("spawned foods lock poisoned");
+        let next_id = startup.len() + spawned.len();
+        if payload.food_id != next_id {
+            return Err(format!(
+                "food_spawn slot id {} skips ahead of next free slot {}",
+                payload.food_id, next_id
+            ));
+        }
+        spawned.push(AtomicFoodSlot::new_present(payload.x, payload.y));
+        self.loose_food_count.fetch_add(1, Ordering::Relaxed);
+        Ok(1)
     }
 
     /// Apply one event.  Returns the signed change to the loose-food count
@@ -175,40 +242,46 @@ impl SimHandle {
             EventPayload::SimFoodSnapshot(payload) => {
                 self.apply_food_snapshot(payload)?
             }
+            EventPayload::SimAntSnapshot(payload) => {
+                self.record_event();
+                self.ant_count.store(payload.ants.len(), Ordering::Relaxed);
+                0
+            }
             EventPayload::FoodPickup(payload) => {
                 self.record_event();
                 self.pickup_count.fetch_add(1, Ordering::Relaxed);
-                self.foods
-                    .get()
-                    .and_then(|foods| foods.get(payload.food_id))
-                    .map(|slot| {
-                        if slot.is_present() {
-                            slot.store_absent();
-                            self.loose_food_count.fetch_sub(1, Ordering::Relaxed);
-                            -1isize
-                        } else {
-                            0
-                        }
-                    })
-                    .unwrap_or(0)
+                self.with_food_slot(payload.food_id, |slot| {
+                    if slot.is_present() {
+                        slot.store_absent();
+                        self.loose_food_count.fetch_sub(1, Ordering::Relaxed);
+                        -1isize
+                    } else {
+                        0
+                    }
+                })
+                .unwrap_or(0)
             }
             EventPayload::FoodDrop(payload) => {
                 self.record_event();
                 self.drop_count.fetch_add(1, Ordering::Relaxed);
-                self.foods
-                    .get()
-                    .and_then(|foods| foods.get(payload.food_id))
-                    .map(|slot| {
-                        let was_absent = !slot.is_present();
-                        slot.store_present(payload.x, payload.y);
-                        if was_absent {
-                            self.loose_food_count.fetch_add(1, Ordering::Relaxed);
-                            1isize
-                        } else {
-                            0
-                        }
-                    })
-                    .unwrap_or(0)
+                self.with_food_slot(payload.food_id, |slot| {
+                    self.place_food(slot, payload.x, payload.y)
+                })
+                .unwrap_or(0)
+            }
+            EventPayload::FoodSpawn(payload) => self.apply_food_spawn(payload)?,
+            EventPayload::FoodRemove(payload) => {
+                self.record_event();
+                self.with_food_slot(payload.food_id, |slot| {
+                    if slot.is_present() {
+                        slot.store_absent();
+                        self.loose_food_count.fetch_sub(1, Ordering::Relaxed);
+                        -1isize
+                    } else {
+                        0
+                    }
+                })
+                .unwrap_or(0)
             }
             EventPayload::AntTurnMove(_) => {
                 self.record_event();
@@ -220,7 +293,8 @@ impl SimHandle {
                 0
             }
             EventPayload::SimGoodbye(_) => {
-                return Err(format!("unsupported event type: {}", envelope.event_type));
+                self.record_event();
+                0
             }
         };
         Ok(EventOutcome {
@@ -286,6 +360,10 @@ impl Registry {
             .clone()
     }
 
+    pub fn get(&self, sim_id: &str) -> Option<Arc<SimHandle>> {
+        self.sims.read().expect("registry read lock poisoned").get(sim_id).cloned()
+    }
+
     /// Clone all current sim handles.  Brief read lock only.
     pub fn all_handles(&self) -> Vec<Arc<SimHandle>> {
         #[cfg(test)]
@@ -319,13 +397,15 @@ impl Registry {
     }
 
     /// Gather loose food positions for analytics computation.
-    /// Reads atomic food slots directly -- no locks held during the scan.
+    /// Reads atomic food slots directly -- only the spawned-food read lock is
+    /// held during the scan.
     pub(crate) fn analytics_input_data(&self) -> AnalyticsInputData {
         let handles = self.all_handles();
         let mut loose_food = Vec::new();
         for handle in &handles {
             if let Some(foods) = handle.foods.get() {
-                for slot in foods.iter() {
+                let spawned = handle.spawned_foods.read().expect("spawned foods lock poisoned");
+                for slot in foods.iter().chain(spawned.iter()) {
                     if let Some((x, y)) = slot.load() {
                         loose_food.push(FoodPosition { x: x as f64, y: y as f64 });
                     }
@@ -339,11 +419,7 @@ impl Registry {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub(crate) struct FoodPosition {
-    pub x: f64,
-    pub y: f64,
-}
+pub(crate) use gatherers_analytics::FoodPosition;
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct AnalyticsInputData {
@@ -356,53 +432,14 @@ impl AnalyticsInputData {
         if self.loose_food.is_empty() {
             return AnalyticsSummaryResponse::default();
         }
+        let summary = ClusteringSummary::from_positions(&self.loose_food, self.cell_size);
         AnalyticsSummaryResponse {
-            occupied_cell_count: occupied_cell_count(&self.loose_food, self.cell_size),
-            nearest_neighbor_mean_distance: mean_nearest_neighbor_distance(&self.loose_food),
+            occupied_cell_count: summary.occupied_cell_count,
+            nearest_neighbor_mean_distance: summary.nearest_neighbor_mean_distance,
         }
     }
 }
 
-fn occupied_cell_count(positions: &[FoodPosition], cell_size: f64) -> usize {
-    let effective_cell_size = if cell_size > 0.0 { cell_size } else { 1.0 };
-    let mut cells: HashMap<(i64, i64), ()> = HashMap::new();
-    for position in positions {
-        let cell = (
-            (position.x / effective_cell_size).floor() as i64,
-            (position.y / effective_cell_size).floor() as i64,
-        );
-        cells.insert(cell, ());
-    }
-    cells.len()
-}
-
-fn mean_nearest_neighbor_distance(positions: &[FoodPosition]) -> f64 {
-    if positions.len() < 2 {
-        return 0.0;
-    }
-
-    let total = positions
-        .iter()
-        .enumerate()
-        .map(|(index, position)| {
-            positions
-                .iter()
-                .enumerate()
-                .filter_map(|(other_index, other)| {
-                    if index == other_index {
-                        return None;
-                    }
-                    Some(
-                        ((position.x - other.x).powi(2) + (position.y - other.y).powi(2)).sqrt(),
-                    )
-                })
-                .fold(f64::MAX, f64::min)
-        })
-        .sum::<f64>();
-
-    total / positions.len() as f64
-}
-
 #[cfg(test)]
 mod atomic_slot_tests {
     use super::*;
@@ -453,8 +490,11 @@ mod atomic_slot_tests {
             event_type: match &payload {
                 EventPayload::SimHello(_) => "sim_hello",
                 EventPayload::SimFoodSnapshot(_) => "sim_food_snapshot",
+                EventPayload::SimAntSnapshot(_) => "sim_ant_snapshot",
                 EventPayload::FoodPickup(_) => "food_pickup",
                 EventPayload::FoodDrop(_) => "food_drop",
+                EventPayload::FoodSpawn(_) => "food_spawn",
+                EventPayload::FoodRemove(_) => "food_remove",
                 EventPayload::AntTurnMove(_) => "ant_turn_move",
                 EventPayload::SimHeartbeat(_) => "sim_heartbeat",
                 EventPayload::SimGoodbye(_) => "sim_goodbye",
@@ -471,6 +511,7 @@ mod atomic_slot_tests {
         make_envelope(
             sim_id,
             EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                slot_count: None,
                 foods: vec![
                     StartupFoodPayload { food_id: 0, x: 10.0, y: 20.0 },
                     StartupFoodPayload { food_id: 1, x: 30.0, y: 40.0 },
@@ -483,7 +524,7 @@ mod atomic_slot_tests {
     fn snapshot_with_foods(sim_id: &str, foods: Vec<StartupFoodPayload>) -> EventEnvelope {
         make_envelope(
             sim_id,
-            EventPayload::SimFoodSnapshot(FoodSnapshotPayload { foods }),
+            EventPayload::SimFoodSnapshot(FoodSnapshotPayload { slot_count: None, foods }),
         )
     }
 
@@ -546,6 +587,99 @@ mod atomic_slot_tests {
         assert_eq!(foods[2].load(), Some((50.0, 60.0)));
     }
 
+    fn sparse_snapshot(
+        sim_id: &str,
+        slot_count: usize,
+        foods: Vec<StartupFoodPayload>,
+    ) -> EventEnvelope {
+        make_envelope(
+            sim_id,
+            EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                slot_count: Some(slot_count),
+                foods,
+            }),
+        )
+    }
+
+    #[test]
+    fn sparse_snapshot_marks_unlisted_slots_absent() {
+        let handle = SimHandle::new("sim-a".into());
+        let outcome = handle
+            .apply_event(&sparse_snapshot(
+                "sim-a",
+                3,
+                vec![StartupFoodPayload { food_id: 2, x: 50.0, y: 60.0 }],
+            ))
+            .unwrap();
+
+        assert_eq!(outcome.loose_food_delta, 1);
+        let foods = handle.foods.get().expect("slot array should be installed");
+        assert_eq!(foods.len(), 3);
+        assert!(!foods[0].is_present());
+        assert!(!foods[1].is_present());
+        assert_eq!(foods[2].load(), Some((50.0, 60.0)));
+    }
+
+    #[test]
+    fn resync_snapshot_replaces_known_slots_and_adds_spawned_ones() {
+        let handle = SimHandle::new("sim-a".into());
+        handle.apply_event(&snapshot_3_foods("sim-a")).unwrap();
+
+        // Slot 1 is carried, slot 3 was spawned while disconnected
+        let outcome = handle
+            .apply_event(&sparse_snapshot(
+                "sim-a",
+                4,
+                vec![
+                    StartupFoodPayload { food_id: 0, x: 11.0, y: 21.0 },
+                    StartupFoodPayload { food_id: 2, x: 51.0, y: 61.0 },
+                    StartupFoodPayload { food_id: 3, x: 70.0, y: 80.0 },
+                ],
+            ))
+            .unwrap();
+
+        assert_eq!(outcome.loose_food_delta, 0);
+        assert_eq!(handle.loose_food_count.load(Ordering::Relaxed), 3);
+        let foods = handle.foods.get().unwrap();
+        assert_eq!(foods[0].load(), Some((11.0, 21.0)));
+        assert!(!foods[1].is_present());
+        assert_eq!(handle.with_food_slot(3, AtomicFoodSlot::load), Some(Some((70.0, 80.0))));
+
+        // A drop of the carried food is tracked again
+        let outcome = handle
+            .apply_event(&make_envelope(
+                "sim-a",
+                EventPayload::FoodDrop(FoodDropPayload {
+                    ant_id: None,
+                    colony_id: None,
+                    food_id: 1,
+                    x: 5.0,
+                    y: 6.0,
+                    direction_x: None,
+                    direction_y: None,
+                    frame: None,
+                }),
+            ))
+            .unwrap();
+        assert_eq!(outcome.loose_food_delta, 1);
+    }
+
+    #[test]
+    fn snapshot_slot_ids_must_fit_the_slot_count() {
+        let handle = SimHandle::new("sim-a".into());
+        let error = match handle.apply_event(&sparse_snapshot(
+            "sim-a",
+            2,
+            vec![StartupFoodPayload { food_id: 2, x: 50.0, y: 60.0 }],
+        )) {
+            Ok(_) => panic!("slot id past slot_count should be rejected"),
+            Err(error) => error,
+        };
+
+        assert!(error.contains("out of range"), "got {error}");
+        assert!(handle.foods.get().is_none());
+    }
+
     #[test]
     fn pickup_clears_slot_and_decrements_count() {
         let handle = SimHandle::new("sim-a".into());
@@ -556,6 +690,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodPickup(FoodPickupPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 1,
                     x: None,
                     y: None,
@@ -582,6 +717,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodPickup(FoodPickupPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 0,
                     x: None,
                     y: None,
@@ -597,6 +733,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodDrop(FoodDropPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 0,
                     x: 99.0,
                     y: 88.0,
@@ -626,6 +763,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodPickup(FoodPickupPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 0,
                     x: None,
                     y: None,
@@ -640,6 +778,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodDrop(FoodDropPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 0,
                     x: 99.0,
                     y: 88.0,
@@ -654,6 +793,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodDrop(FoodDropPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 0,
                     x: 77.0,
                     y: 66.0,
@@ -678,6 +818,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodPickup(FoodPickupPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: usize::MAX,
                     x: None,
                     y: None,
@@ -702,6 +843,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodDrop(FoodDropPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 999,
                     x: 1.0,
                     y: 2.0,
@@ -726,6 +868,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodPickup(FoodPickupPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 1,
                     x: None,
                     y: None,
@@ -755,6 +898,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodPickup(FoodPickupPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 0,
                     x: None,
                     y: None,
@@ -769,6 +913,7 @@ mod atomic_slot_tests {
                 "sim-a",
                 EventPayload::FoodDrop(FoodDropPayload {
                     ant_id: None,
+                    colony_id: None,
                     food_id: 0,
                     x: 99.0,
                     y: 88.0,
@@ -784,4 +929,99 @@ mod atomic_slot_tests {
             analytics.loose_food.iter().map(|p| (p.x, p.y)).collect();
         assert!(positions.contains(&(99.0, 88.0)), "slot 0 should be at new drop position");
     }
+
+    fn food_spawn(sim_id: &str, food_id: usize, x: f32, y: f32) -> EventEnvelope {
+        make_envelope(sim_id, EventPayload::FoodSpawn(FoodSpawnPayload { food_id, x, y }))
+    }
+
+    #[test]
+    fn food_spawn_appends_slots_after_the_snapshot() {
+        let reg = Registry::default();
+        let handle = reg.get_or_create("sim-a");
+        handle.apply_event(&snapshot_3_foods("sim-a")).unwrap();
+
+        let outcome = handle.apply_event(&food_spawn("sim-a", 3, 70.0, 80.0)).unwrap();
+        assert_eq!(outcome.loose_food_delta, 1);
+        assert_eq!(outcome.sim_loose_food_count, 4);
+
+        // Spawned slots take part in pickups like startup ones
+        handle
+            .apply_event(&make_envelope(
+                "sim-a",
+                EventPayload::FoodPickup(FoodPickupPayload {
+                    ant_id: None,
+                    colony_id: None,
+                    food_id: 3,
+                    x: None,
+                    y: None,
+                    direction_x: None,
+                    direction_y: None,
+                    frame: None,
+                }),
+            ))
+            .unwrap();
+        assert_eq!(handle.loose_food_count.load(Ordering::Relaxed), 3);
+
+        let outcome = handle.apply_event(&food_spawn("sim-a", 3, 1.0, 2.0)).unwrap();
+        assert_eq!(outcome.loose_food_delta, 1, "re-announced slot is placed again");
+        let positions: Vec<(f64, f64)> =
+            reg.analytics_input_data().loose_food.iter().map(|p| (p.x, p.y)).collect();
+        assert!(positions.contains(&(1.0, 2.0)));
+    }
+
+    #[test]
+    fn food_spawn_requires_snapshot_and_the_next_free_slot() {
+        let handle = SimHandle::new("sim-a".into());
+        let error = handle
+            .apply_event(&food_spawn("sim-a", 0, 1.0, 2.0))
+            .err()
+            .expect("spawn before snapshot should be rejected");
+        assert!(error.contains("sim_food_snapshot"), "got {error}");
+
+        handle.apply_event(&snapshot_3_foods("sim-a")).unwrap();
+        let error = handle
+            .apply_event(&food_spawn("sim-a", 5, 1.0, 2.0))
+            .err()
+            .expect("spawn skipping slot ids should be rejected");
+        assert!(error.contains("next free slot 3"), "got {error}");
+        assert_eq!(handle.loose_food_count.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn food_remove_clears_startup_and_spawned_slots() {
+        let handle = SimHandle::new("sim-a".into());
+        handle.apply_event(&snapshot_3_foods("sim-a")).unwrap();
+        handle.apply_event(&food_spawn("sim-a", 3, 70.0, 80.0)).unwrap();
+
+        for food_id in [0, 3, 3] {
+            handle
+                .apply_event(&make_envelope(
+                    "sim-a",
+                    EventPayload::FoodRemove(FoodRemovePayload { food_id }),
+                ))
+                .unwrap();
+        }
+        assert_eq!(handle.loose_food_count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn ant_snapshot_sets_the_ant_count() {
+        let handle = SimHandle::new("sim-a".into());
+        let ants = (0..4)
+            .map(|id| StartupAntPayload {
+                ant_id: id.to_string(),
+                colony_id: 0,
+                x: id as f32,
+                y: 0.0,
+            })
+            .collect();
+        let outcome = handle
+            .apply_event(&make_envelope(
+                "sim-a",
+                EventPayload::SimAntSnapshot(AntSnapshotPayload { ants }),
+            ))
+            .unwrap();
+        assert_eq!(outcome.ant_count, 4);
+        assert_eq!(outcome.loose_food_delta, 0);
+    }
 }
@@ -48,6 +48,19 @@ pub struct SimSummaryResponse {
     pub loose_food_count: usize,
 }
 
+/// The sims a command was queued for.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SimCommandResponse {
+    pub sent_to: Vec<String>,
+}
+
+/// A sim that left with `sim_goodbye`, and the reason it gave.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SimDepartureResponse {
+    pub sim_id: String,
+    pub reason: String,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Serialize)]
 pub struct BreakpointTotalsResponse {
     pub connected_sims: usize,
@@ -189,6 +189,7 @@ async fn api_summary_exposes_live_counts_immediately_and_analytics_catch_up_afte
         seq: 2,
         timestamp_ms: 0,
         payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+            slot_count: None,
             foods: vec![
                 StartupFoodPayload {
                     food_id: 0,
@@ -268,6 +269,7 @@ async fn api_summary_keeps_live_counts_fresh_even_when_analytics_are_stale() {
         seq: 2,
         timestamp_ms: 0,
         payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+            slot_count: None,
             foods: vec![
                 StartupFoodPayload { food_id: 0, x: 12.0, y: 18.0 },
                 StartupFoodPayload { food_id: 1, x: 22.0, y: 28.0 },
@@ -282,6 +284,7 @@ async fn api_summary_keeps_live_counts_fresh_even_when_analytics_are_stale() {
         timestamp_ms: 0,
         payload: EventPayload::FoodPickup(FoodPickupPayload {
             ant_id: Some("ant-1".into()),
+            colony_id: None,
             food_id: 0,
             x: None, y: None, direction_x: None, direction_y: None, frame: None,
         }),
@@ -294,6 +297,7 @@ async fn api_summary_keeps_live_counts_fresh_even_when_analytics_are_stale() {
         timestamp_ms: 0,
         payload: EventPayload::FoodPickup(FoodPickupPayload {
             ant_id: Some("ant-1".into()),
+            colony_id: None,
             food_id: 1,
             x: None, y: None, direction_x: None, direction_y: None, frame: None,
         }),
@@ -306,6 +310,7 @@ async fn api_summary_keeps_live_counts_fresh_even_when_analytics_are_stale() {
         timestamp_ms: 0,
         payload: EventPayload::FoodDrop(FoodDropPayload {
             ant_id: Some("ant-1".into()),
+            colony_id: None,
             food_id: 0,
             x: 12.0,
             y: 18.0,
@@ -333,6 +338,7 @@ async fn api_summary_keeps_live_counts_fresh_even_when_analytics_are_stale() {
         timestamp_ms: 0,
         payload: EventPayload::FoodDrop(FoodDropPayload {
             ant_id: Some("ant-1".into()),
+            colony_id: None,
             food_id: 1,
             x: 22.0,
             y: 28.0,
@@ -388,6 +394,7 @@ async fn api_sims_catches_up_to_direct_ingest_counts() {
         seq: 2,
         timestamp_ms: 0,
         payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+            slot_count: None,
             foods: vec![
                 StartupFoodPayload { food_id: 0, x: 10.0, y: 20.0 },
                 StartupFoodPayload { food_id: 1, x: 30.0, y: 40.0 },
@@ -403,6 +410,7 @@ async fn api_sims_catches_up_to_direct_ingest_counts() {
         timestamp_ms: 0,
         payload: EventPayload::FoodPickup(FoodPickupPayload {
             ant_id: Some("ant-1".into()),
+            colony_id: None,
             food_id: 0,
             x: Some(10.0),
             y: Some(20.0),
@@ -419,6 +427,7 @@ async fn api_sims_catches_up_to_direct_ingest_counts() {
         timestamp_ms: 0,
         payload: EventPayload::FoodDrop(FoodDropPayload {
             ant_id: Some("ant-1".into()),
+            colony_id: None,
             food_id: 0,
             x: 70.0,
             y: 80.0,
@@ -435,6 +444,7 @@ async fn api_sims_catches_up_to_direct_ingest_counts() {
         timestamp_ms: 0,
         payload: EventPayload::AntTurnMove(TurnMovePayload {
             ant_id: "ant-1".into(),
+            colony_id: None,
             x: 70.0,
             y: 80.0,
             direction_x: 0.0,
@@ -540,6 +550,7 @@ async fn breakpoint_totals_aggregates_only_matching_prefix() {
             seq: 2,
             timestamp_ms: 0,
             payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                slot_count: None,
                 foods: vec![
                     StartupFoodPayload { food_id: 0, x: base_x, y: base_x },
                     StartupFoodPayload { food_id: 1, x: base_x + 1.0, y: base_x + 1.0 },
@@ -558,6 +569,7 @@ async fn breakpoint_totals_aggregates_only_matching_prefix() {
             timestamp_ms: 0,
             payload: EventPayload::FoodPickup(FoodPickupPayload {
                 ant_id: Some(format!("{sim_id}-ant")),
+                colony_id: None,
                 food_id: 0,
                 x: None,
                 y: None,
@@ -574,6 +586,7 @@ async fn breakpoint_totals_aggregates_only_matching_prefix() {
             timestamp_ms: 0,
             payload: EventPayload::FoodDrop(FoodDropPayload {
                 ant_id: Some(format!("{sim_id}-ant")),
+                colony_id: None,
                 food_id: 0,
                 x: 99.0,
                 y: 101.0,
@@ -590,6 +603,7 @@ async fn breakpoint_totals_aggregates_only_matching_prefix() {
             timestamp_ms: 0,
             payload: EventPayload::AntTurnMove(TurnMovePayload {
                 ant_id: format!("{sim_id}-ant"),
+                colony_id: None,
                 x: 99.0,
                 y: 101.0,
                 direction_x: 0.0,
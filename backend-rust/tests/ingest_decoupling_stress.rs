@@ -89,6 +89,7 @@ fn client_events(sim_id: &str, index: usize) -> Vec<EventEnvelope> {
         seq: 2,
         timestamp_ms: 0,
         payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+            slot_count: None,
             foods: (0..INITIAL_FOOD_COUNT)
                 .map(|food_index| StartupFoodPayload {
                     food_id: food_index,
@@ -112,6 +113,7 @@ fn client_events(sim_id: &str, index: usize) -> Vec<EventEnvelope> {
             timestamp_ms: 0,
             payload: EventPayload::FoodPickup(FoodPickupPayload {
                 ant_id: Some(ant_id.clone()),
+                colony_id: None,
                 food_id: triplet,
                 x: Some(x),
                 y: Some(y),
@@ -129,6 +131,7 @@ fn client_events(sim_id: &str, index: usize) -> Vec<EventEnvelope> {
             timestamp_ms: 0,
             payload: EventPayload::FoodDrop(FoodDropPayload {
                 ant_id: Some(ant_id.clone()),
+                colony_id: None,
                 food_id: triplet,
                 x: x + 0.5,
                 y: y + 0.5,
@@ -146,6 +149,7 @@ fn client_events(sim_id: &str, index: usize) -> Vec<EventEnvelope> {
             timestamp_ms: 0,
             payload: EventPayload::AntTurnMove(TurnMovePayload {
                 ant_id,
+                colony_id: None,
                 x: x + 1.0,
                 y: y + 1.0,
                 direction_x: 0.0,
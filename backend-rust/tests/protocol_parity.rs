@@ -62,7 +62,8 @@ fn deserializes_sim_food_snapshot_from_current_client_shape() {
     .expect("sim_food_snapshot should deserialize");
 
     match envelope.payload {
-        EventPayload::SimFoodSnapshot(FoodSnapshotPayload { foods }) => {
+        EventPayload::SimFoodSnapshot(FoodSnapshotPayload { slot_count, foods }) => {
+            assert_eq!(slot_count, None);
             assert_eq!(
                 foods,
                 vec![
@@ -93,6 +94,7 @@ fn deserializes_food_pickup_and_drop_from_current_client_shape() {
             "timestamp_ms": 0,
             "payload": {
                 "ant_id": "ant-1",
+                "colony_id": 1,
                 "food_id": 0,
                 "x": 1.0,
                 "y": 2.0,
@@ -106,9 +108,13 @@ fn deserializes_food_pickup_and_drop_from_current_client_shape() {
 
     match pickup.payload {
         EventPayload::FoodPickup(FoodPickupPayload {
-            ant_id, food_id, ..
+            ant_id,
+            colony_id,
+            food_id,
+            ..
         }) => {
             assert_eq!(ant_id.as_deref(), Some("ant-1"));
+            assert_eq!(colony_id, Some(1));
             assert_eq!(food_id, 0);
         }
         other => panic!("expected food_pickup payload, got {other:?}"),
@@ -122,6 +128,7 @@ fn deserializes_food_pickup_and_drop_from_current_client_shape() {
             "timestamp_ms": 0,
             "payload": {
                 "ant_id": "ant-1",
+                "colony_id": 1,
                 "food_id": 0,
                 "x": 3.0,
                 "y": 4.0,
@@ -135,15 +142,40 @@ fn deserializes_food_pickup_and_drop_from_current_client_shape() {
 
     match drop_event.payload {
         EventPayload::FoodDrop(FoodDropPayload {
-            ant_id, food_id, ..
+            ant_id,
+            colony_id,
+            food_id,
+            ..
         }) => {
             assert_eq!(ant_id.as_deref(), Some("ant-1"));
+            assert_eq!(colony_id, Some(1));
             assert_eq!(food_id, 0);
         }
         other => panic!("expected food_drop payload, got {other:?}"),
     }
 }
 
+#[test]
+fn colony_id_is_optional_for_older_clients() {
+    let envelope: EventEnvelope = serde_json::from_str(
+        r#"{
+            "type": "food_pickup",
+            "sim_id": "sim-123",
+            "seq": 3,
+            "timestamp_ms": 0,
+            "payload": { "ant_id": "ant-1", "food_id": 0 }
+        }"#,
+    )
+    .expect("food_pickup without colony_id should deserialize");
+
+    match envelope.payload {
+        EventPayload::FoodPickup(FoodPickupPayload { colony_id, .. }) => {
+            assert_eq!(colony_id, None);
+        }
+        other => panic!("expected food_pickup payload, got {other:?}"),
+    }
+}
+
 #[test]
 fn deserializes_ant_turn_move_from_current_client_shape() {
     let envelope: EventEnvelope = serde_json::from_str(
@@ -154,6 +186,7 @@ fn deserializes_ant_turn_move_from_current_client_shape() {
             "timestamp_ms": 0,
             "payload": {
                 "ant_id": "ant-1",
+                "colony_id": 1,
                 "x": 3.0,
                 "y": 4.0,
                 "direction_x": 0.25,
@@ -165,8 +198,14 @@ fn deserializes_ant_turn_move_from_current_client_shape() {
     .expect("ant_turn_move should deserialize");
 
     match envelope.payload {
-        EventPayload::AntTurnMove(TurnMovePayload { ant_id, frame, .. }) => {
+        EventPayload::AntTurnMove(TurnMovePayload {
+            ant_id,
+            colony_id,
+            frame,
+            ..
+        }) => {
             assert_eq!(ant_id, "ant-1");
+            assert_eq!(colony_id, Some(1));
             assert_eq!(frame, 8);
         }
         other => panic!("expected ant_turn_move payload, got {other:?}"),
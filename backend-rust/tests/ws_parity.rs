@@ -5,10 +5,10 @@ use gatherers_backend_rust::{
     app::{AppState, build_router_with_state},
     protocol::{
         EventEnvelope, EventPayload, FoodDropPayload, FoodPickupPayload,
-        FoodSnapshotPayload, HeartbeatPayload, HelloPayload, StartupFoodPayload,
+        FoodSnapshotPayload, GoodbyePayload, HeartbeatPayload, HelloPayload, StartupFoodPayload,
     },
 };
-use serde_json::Value;
+use serde_json::{Value, json};
 use tokio::net::TcpListener;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
@@ -52,6 +52,7 @@ async fn dashboard_websocket_receives_update_after_ingest_websocket_events() {
             seq: 2,
             timestamp_ms: 0,
             payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                slot_count: None,
                 foods: vec![StartupFoodPayload { food_id: 0, x: 25.0, y: 25.0 }],
             }),
         },
@@ -62,6 +63,7 @@ async fn dashboard_websocket_receives_update_after_ingest_websocket_events() {
             timestamp_ms: 0,
             payload: EventPayload::FoodPickup(FoodPickupPayload {
                 ant_id: Some("ant-1".into()),
+                colony_id: None,
                 food_id: 0,
                 x: None, y: None, direction_x: None, direction_y: None, frame: None,
             }),
@@ -73,6 +75,7 @@ async fn dashboard_websocket_receives_update_after_ingest_websocket_events() {
             timestamp_ms: 0,
             payload: EventPayload::FoodDrop(FoodDropPayload {
                 ant_id: Some("ant-1".into()),
+                colony_id: None,
                 food_id: 0,
                 x: 25.0,
                 y: 25.0,
@@ -132,6 +135,7 @@ async fn dashboard_websocket_exposes_live_counts_immediately_then_analytics_catc
         seq: 2,
         timestamp_ms: 0,
         payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+            slot_count: None,
             foods: vec![StartupFoodPayload { food_id: 0, x: 12.0, y: 18.0 }],
         }),
     })
@@ -262,6 +266,72 @@ async fn dashboard_websocket_removes_sim_after_ingest_socket_closes() {
     assert_eq!(disconnected["summary"]["connected_sim_count"], 0);
 }
 
+#[tokio::test]
+async fn dashboard_websocket_removes_sim_after_ingest_goodbye() {
+    let state = AppState::new();
+    let base_url = spawn_test_server(state.clone()).await;
+
+    let (mut dashboard_ws, _) = connect_async(format!("{base_url}/ws/dashboard"))
+        .await
+        .expect("dashboard websocket should connect");
+    let _initial = read_json_message(&mut dashboard_ws).await;
+
+    let (mut ingest_ws, _) = connect_async(format!("{base_url}/ws/ingest"))
+        .await
+        .expect("ingest websocket should connect");
+
+    for envelope in [
+        EventEnvelope {
+            event_type: "sim_hello".into(),
+            sim_id: "sim-goodbye".into(),
+            seq: 1,
+            timestamp_ms: 0,
+            payload: EventPayload::SimHello(HelloPayload {
+                sim_name: "sim-goodbye".into(),
+                source: "rust-bevy".into(),
+                session_started_ms: 0,
+                world_width: 1280.0,
+                world_height: 720.0,
+                ant_count: 26,
+                food_count: 0,
+            }),
+        },
+        EventEnvelope {
+            event_type: "sim_goodbye".into(),
+            sim_id: "sim-goodbye".into(),
+            seq: 2,
+            timestamp_ms: 0,
+            payload: EventPayload::SimGoodbye(GoodbyePayload {
+                reason: "shutdown".into(),
+            }),
+        },
+    ] {
+        ingest_ws
+            .send(Message::Text(
+                serde_json::to_string(&envelope).expect("event json").into(),
+            ))
+            .await
+            .expect("event send");
+    }
+
+    // The socket stays open; the goodbye alone removes the sim
+    let disconnected = tokio::time::timeout(
+        std::time::Duration::from_millis(500),
+        wait_for_json_message(&mut dashboard_ws, |json| {
+            json["summary"]["connected_sim_count"] == 0
+                && json["sims"]
+                    .as_array()
+                    .map(|items| items.is_empty())
+                    .unwrap_or(false)
+        }),
+    )
+    .await
+    .expect("dashboard should observe sim removal after sim_goodbye");
+
+    assert_eq!(disconnected["summary"]["connected_sim_count"], 0);
+    assert_eq!(state.recent_departures()[0].reason, "shutdown");
+}
+
 #[tokio::test]
 async fn ingest_websocket_accepts_heartbeat_without_disconnect() {
     let state = AppState::new();
@@ -309,6 +379,7 @@ async fn ingest_websocket_accepts_heartbeat_without_disconnect() {
             seq: 3,
             timestamp_ms: 0,
             payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+                slot_count: None,
                 foods: vec![StartupFoodPayload { food_id: 0, x: 25.0, y: 35.0 }],
             }),
         },
@@ -337,6 +408,189 @@ async fn ingest_websocket_accepts_heartbeat_without_disconnect() {
     assert_eq!(update["summary"]["loose_food_count"], 1);
 }
 
+#[tokio::test]
+async fn ingest_websocket_hello_restarts_the_session_after_a_reset() {
+    let state = AppState::new();
+    let base_url = spawn_test_server(state.clone()).await;
+
+    let (mut dashboard_ws, _) = connect_async(format!("{base_url}/ws/dashboard"))
+        .await
+        .expect("dashboard websocket should connect");
+    let _initial = read_json_message(&mut dashboard_ws).await;
+
+    let (mut ingest_ws, _) = connect_async(format!("{base_url}/ws/ingest"))
+        .await
+        .expect("ingest websocket should connect");
+
+    let hello = |seq| EventEnvelope {
+        event_type: "sim_hello".into(),
+        sim_id: "sim-reset".into(),
+        seq,
+        timestamp_ms: 0,
+        payload: EventPayload::SimHello(HelloPayload {
+            sim_name: "sim-reset".into(),
+            source: "rust-bevy".into(),
+            session_started_ms: 0,
+            world_width: 1280.0,
+            world_height: 720.0,
+            ant_count: 1,
+            food_count: 2,
+        }),
+    };
+    let snapshot = |seq, count| EventEnvelope {
+        event_type: "sim_food_snapshot".into(),
+        sim_id: "sim-reset".into(),
+        seq,
+        timestamp_ms: 0,
+        payload: EventPayload::SimFoodSnapshot(FoodSnapshotPayload {
+            slot_count: None,
+            foods: (0..count)
+                .map(|food_id| StartupFoodPayload {
+                    food_id,
+                    x: 10.0,
+                    y: 10.0,
+                })
+                .collect(),
+        }),
+    };
+    let pickup = EventEnvelope {
+        event_type: "food_pickup".into(),
+        sim_id: "sim-reset".into(),
+        seq: 3,
+        timestamp_ms: 0,
+        payload: EventPayload::FoodPickup(FoodPickupPayload {
+            ant_id: Some("0".into()),
+            colony_id: Some(0),
+            food_id: 2,
+            x: Some(10.0),
+            y: Some(10.0),
+            direction_x: None,
+            direction_y: None,
+            frame: Some(1),
+        }),
+    };
+
+    // The reset world has fewer food slots than the first session
+    for envelope in [hello(1), snapshot(2, 3), pickup, hello(1), snapshot(2, 2)] {
+        ingest_ws
+            .send(Message::Text(
+                serde_json::to_string(&envelope).expect("event json").into(),
+            ))
+            .await
+            .expect("event send");
+    }
+
+    let update = tokio::time::timeout(
+        std::time::Duration::from_millis(750),
+        wait_for_json_message(&mut dashboard_ws, |json| {
+            json["summary"]["connected_sim_count"] == 1
+                && json["sims"][0]["sim_id"] == "sim-reset"
+                && json["sims"][0]["pickup_count"] == 0
+                && json["sims"][0]["loose_food_count"] == 2
+        }),
+    )
+    .await
+    .expect("second sim_hello should start a fresh session on the same socket");
+
+    assert_eq!(update["summary"]["loose_food_count"], 2);
+}
+
+#[tokio::test]
+async fn command_endpoint_sends_commands_down_matching_ingest_sockets() {
+    let state = AppState::new();
+    let base_url = spawn_test_server(state.clone()).await;
+    let http_url = base_url.replacen("ws://", "http://", 1);
+    let client = reqwest::Client::new();
+
+    let mut sockets = Vec::new();
+    for sim_id in ["batch-a", "batch-b", "solo"] {
+        let (mut ingest_ws, _) = connect_async(format!("{base_url}/ws/ingest"))
+            .await
+            .expect("ingest websocket should connect");
+        let hello = EventEnvelope {
+            event_type: "sim_hello".into(),
+            sim_id: sim_id.into(),
+            seq: 1,
+            timestamp_ms: 0,
+            payload: EventPayload::SimHello(HelloPayload {
+                sim_name: sim_id.into(),
+                source: "rust-bevy".into(),
+                session_started_ms: 0,
+                world_width: 1280.0,
+                world_height: 720.0,
+                ant_count: 1,
+                food_count: 0,
+            }),
+        };
+        ingest_ws
+            .send(Message::Text(
+                serde_json::to_string(&hello).expect("event json").into(),
+            ))
+            .await
+            .expect("event send");
+        sockets.push(ingest_ws);
+    }
+
+    let post_command = |body: Value| {
+        let request = client
+            .post(format!("{http_url}/api/sims/command"))
+            .json(&body);
+        async move { request.send().await.expect("command request") }
+    };
+
+    // The sockets register their sims once the hello is applied
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(1500);
+    let sent_to = loop {
+        let response = post_command(json!({
+            "prefix": "batch-",
+            "command": { "command": "reset", "seed": 7 },
+        }))
+        .await;
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.expect("command response json");
+        if body["sent_to"]
+            .as_array()
+            .is_some_and(|sims| sims.len() == 2)
+        {
+            break body["sent_to"].clone();
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "expected both batch sims to register, last response was {body:?}"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(25)).await;
+    };
+    assert_eq!(sent_to, json!(["batch-a", "batch-b"]));
+
+    for (ingest_ws, sim_id) in sockets.iter_mut().zip(["batch-a", "batch-b"]) {
+        let command = read_json_message(ingest_ws).await;
+        assert_eq!(command["type"], "sim_command");
+        assert_eq!(command["sim_id"], sim_id);
+        assert_eq!(command["payload"], json!({ "command": "reset", "seed": 7 }));
+    }
+
+    let response = post_command(json!({
+        "sim_id": "solo",
+        "command": { "command": "set_speed", "speed": 2.5 },
+    }))
+    .await;
+    assert_eq!(response.status(), 200);
+    let command = read_json_message(&mut sockets[2]).await;
+    assert_eq!(
+        command["payload"],
+        json!({ "command": "set_speed", "speed": 2.5 })
+    );
+
+    let missing = post_command(json!({
+        "sim_id": "missing",
+        "command": { "command": "pause" },
+    }))
+    .await;
+    assert_eq!(missing.status(), 404);
+    let ambiguous = post_command(json!({ "command": { "command": "pause" } })).await;
+    assert_eq!(ambiguous.status(), 400);
+}
+
 async fn spawn_test_server(state: AppState) -> String {
     let router = build_router_with_state(state);
     let listener = TcpListener::bind("127.0.0.1:0")
@@ -0,0 +1,189 @@
+//! Live clustering metrics over the loose food, from the shared
+//! `gatherers-analytics` crate so the sim reports the same numbers as the
+//! backend's analytics summary.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::Food;
+use crate::config::Config;
+use crate::simulation::{SimReset, SimTick};
+
+pub use gatherers_analytics::{ClusteringSummary, FoodPosition};
+
+/// Refreshes `ClusteringStats` every `Config::ANALYTICS_UPDATE_FRAMES` frames
+/// and records each refresh in `ClusteringHistory`.
+pub struct AnalyticsPlugin;
+
+impl Plugin for AnalyticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClusteringStats>()
+            .init_resource::<ClusteringHistory>()
+            .add_message::<SimReset>()
+            .add_systems(
+                Update,
+                (clear_clustering_history_on_reset, update_clustering_stats).chain(),
+            );
+    }
+}
+
+/// Latest clustering metrics and the sim tick they were computed at.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ClusteringStats {
+    pub summary: ClusteringSummary,
+    pub tick: u64,
+}
+
+/// A metric tracked over time in `ClusteringHistory`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusteringMetric {
+    LooseFood,
+    OccupiedCells,
+    NearestNeighborDistance,
+}
+
+impl ClusteringMetric {
+    pub const ALL: [Self; 3] = [
+        Self::LooseFood,
+        Self::OccupiedCells,
+        Self::NearestNeighborDistance,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::LooseFood => "Loose food",
+            Self::OccupiedCells => "Occupied cells",
+            Self::NearestNeighborDistance => "Nearest neighbour",
+        }
+    }
+
+    pub fn value(self, summary: &ClusteringSummary) -> f64 {
+        match self {
+            Self::LooseFood => summary.loose_food_count as f64,
+            Self::OccupiedCells => summary.occupied_cell_count as f64,
+            Self::NearestNeighborDistance => summary.nearest_neighbor_mean_distance,
+        }
+    }
+}
+
+/// Ring buffer of the last `Config::ANALYTICS_HISTORY_LEN` refreshes,
+/// oldest first, each with the sim tick it was taken at.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ClusteringHistory {
+    samples: VecDeque<(u64, ClusteringSummary)>,
+}
+
+impl ClusteringHistory {
+    pub fn push(&mut self, tick: u64, summary: ClusteringSummary) {
+        // Nothing new to plot while the sim is not ticking
+        if let Some((last_tick, last)) = self.samples.back_mut()
+            && *last_tick == tick
+        {
+            *last = summary;
+            return;
+        }
+        if self.samples.len() == Config::ANALYTICS_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((tick, summary));
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &(u64, ClusteringSummary)> {
+        self.samples.iter()
+    }
+
+    /// Range of `metric` over the buffer, or `None` when empty.
+    pub fn range(&self, metric: ClusteringMetric) -> Option<(f64, f64)> {
+        self.samples
+            .iter()
+            .map(|(_, summary)| metric.value(summary))
+            .fold(None, |range, value| {
+                let (min, max) = range.unwrap_or((value, value));
+                Some((min.min(value), max.max(value)))
+            })
+    }
+
+    /// The samples of `metric` scaled into the unit square: x by sim tick
+    /// across the buffer, y by value across `range`. A flat series sits at 0.5.
+    pub fn normalized(&self, metric: ClusteringMetric) -> Vec<Vec2> {
+        let (Some(&(first_tick, _)), Some(&(last_tick, _)), Some((min, max))) = (
+            self.samples.front(),
+            self.samples.back(),
+            self.range(metric),
+        ) else {
+            return Vec::new();
+        };
+        let tick_span = (last_tick - first_tick) as f64;
+        let value_span = max - min;
+
+        self.samples
+            .iter()
+            .map(|(tick, summary)| {
+                let x = if tick_span > 0.0 {
+                    (tick - first_tick) as f64 / tick_span
+                } else {
+                    1.0
+                };
+                let y = if value_span > 0.0 {
+                    (metric.value(summary) - min) / value_span
+                } else {
+                    0.5
+                };
+                Vec2::new(x as f32, y as f32)
+            })
+            .collect()
+    }
+}
+
+/// Clustering metrics for food lying at `positions`.
+pub fn clustering_summary(positions: &[Vec2], cell_size: f32) -> ClusteringSummary {
+    let positions: Vec<FoodPosition> = positions
+        .iter()
+        .map(|position| FoodPosition {
+            x: position.x as f64,
+            y: position.y as f64,
+        })
+        .collect();
+    ClusteringSummary::from_positions(&positions, cell_size as f64)
+}
+
+/// Tick numbers restart with a reset, so the old samples would not plot.
+fn clear_clustering_history_on_reset(
+    mut resets: MessageReader<SimReset>,
+    mut history: ResMut<ClusteringHistory>,
+) {
+    if resets.read().count() > 0 {
+        *history = ClusteringHistory::default();
+    }
+}
+
+fn update_clustering_stats(
+    mut frame: Local<u32>,
+    tick: Res<SimTick>,
+    mut stats: ResMut<ClusteringStats>,
+    mut history: ResMut<ClusteringHistory>,
+    loose_food: Query<&Transform, (With<Food>, Without<ChildOf>)>,
+) {
+    let due = frame.is_multiple_of(Config::ANALYTICS_UPDATE_FRAMES);
+    *frame = frame.wrapping_add(1);
+    if !due {
+        return;
+    }
+
+    let positions: Vec<Vec2> = loose_food
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    stats.summary = clustering_summary(&positions, Config::ANALYTICS_CELL_SIZE);
+    stats.tick = tick.0;
+    history.push(tick.0, stats.summary.clone());
+}
@@ -0,0 +1,56 @@
+//! Headless batch runner: sweeps seeds and parameters and writes one CSV row
+//! per run. See `gatherers-batch --help`.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use an_gatherers::experiment::{BATCH_USAGE, BatchConfig};
+use an_gatherers::runtime::help_requested;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if help_requested(&args, &[]) {
+        print!("{BATCH_USAGE}");
+        return;
+    }
+    let config = match BatchConfig::from_args(args) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("error: {error}\n\n{BATCH_USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(error) = run(&config) {
+        eprintln!("error: {error}");
+        std::process::exit(1);
+    }
+}
+
+fn run(config: &BatchConfig) -> Result<(), String> {
+    let mut out: Box<dyn Write> = match &config.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|err| {
+                format!("failed to create {}: {err}", path.display())
+            })?))
+        }
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    let write_error = |err: io::Error| format!("failed to write CSV: {err}");
+
+    writeln!(out, "{}", config.csv_header()).map_err(write_error)?;
+
+    let total = config.combinations().len() * config.seeds.len();
+    let mut done = 0;
+    let mut result = Ok(());
+    config.run(|row| {
+        done += 1;
+        eprintln!("[{done}/{total}] seed {} finished", row.seed);
+        if result.is_ok() {
+            result = writeln!(out, "{}", row.to_csv())
+                .and_then(|()| out.flush())
+                .map_err(write_error);
+        }
+    })?;
+    result
+}
@@ -1,13 +1,19 @@
 //! Boundary wrapping system for entities that should wrap around screen edges
 //! Originally from bevydroids
 
-use bevy::{prelude::*, window::PrimaryWindow};
+use crate::collision::PreviousPosition;
+use crate::config::Config;
+use crate::simulation::SimulationSet;
+use bevy::prelude::*;
 
 pub struct BoundaryPlugin;
 
 impl Plugin for BoundaryPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostUpdate, boundary_wrap_system);
+        app.init_resource::<WorldBounds>().add_systems(
+            FixedUpdate,
+            boundary_wrap_system.in_set(SimulationSet::Boundary),
+        );
     }
 }
 
@@ -23,19 +29,47 @@ impl Bounding {
 #[derive(Debug, Component, Default)]
 pub struct BoundaryWrap;
 
+/// Size of the simulated world, centred on the origin.
+/// Decoupled from the window so the world can be larger or smaller than the
+/// viewport and stays the same when the window is resized.
+#[derive(Debug, Resource, Clone, Copy, PartialEq)]
+pub struct WorldBounds {
+    size: Vec2,
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self::new(Config::DEFAULT_WORLD_SIZE)
+    }
+}
+
+impl WorldBounds {
+    pub fn new(size: Vec2) -> Self {
+        Self { size }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn half_size(&self) -> Vec2 {
+        self.size / 2.0
+    }
+}
+
 fn boundary_wrap_system(
-    primary_window: Query<&Window, With<PrimaryWindow>>,
-    mut query: Query<(&mut Transform, &Bounding), With<BoundaryWrap>>,
+    bounds: Res<WorldBounds>,
+    mut query: Query<
+        (&mut Transform, &Bounding, Option<&mut PreviousPosition>),
+        With<BoundaryWrap>,
+    >,
 ) {
-    let Ok(window) = primary_window.single() else {
-        return;
-    };
+    let half_width = bounds.half_size().x;
+    let half_height = bounds.half_size().y;
 
-    let half_width = window.width() / 2.0;
-    let half_height = window.height() / 2.0;
-
-    for (mut transform, bounding) in query.iter_mut() {
+    for (mut transform, bounding, previous) in query.iter_mut() {
         let radius = **bounding;
+        let before = transform.translation.truncate();
         let pos = &mut transform.translation;
 
         // Wrap horizontally when entity goes completely off screen
@@ -51,5 +85,10 @@ fn boundary_wrap_system(
         } else if pos.y - radius > half_height {
             pos.y = -half_height - radius;
         }
+
+        // Carry the swept path across the edge instead of across the world
+        if let Some(mut previous) = previous {
+            previous.0 += pos.truncate() - before;
+        }
     }
 }
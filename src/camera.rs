@@ -0,0 +1,140 @@
+//! The world camera: mouse-wheel zoom about the cursor, right-drag pan and a
+//! key to fit the whole world back in view. UI nodes are laid out in screen
+//! space, so the panels stay put while the world moves underneath.
+
+use bevy::camera::ScalingMode;
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::{AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::boundary::WorldBounds;
+use crate::config::Config;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PanGrab>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(Update, (zoom_camera, pan_camera, fit_world).chain());
+    }
+}
+
+/// The camera showing the simulated world, as opposed to any UI camera.
+#[derive(Component)]
+pub struct WorldCamera;
+
+/// World point held under the cursor while right-dragging.
+#[derive(Resource, Default, Debug)]
+struct PanGrab(Option<Vec2>);
+
+/// Orthographic projection that shows the whole world at scale 1, letterboxed
+/// to the window's aspect ratio, so resizing the window does not change the world.
+pub fn world_projection(map_size: Vec2) -> Projection {
+    Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::AutoMin {
+            min_width: map_size.x,
+            min_height: map_size.y,
+        },
+        ..OrthographicProjection::default_2d()
+    })
+}
+
+/// Camera centre and projection scale after zooming by `factor` so that the
+/// world point `anchor` stays under the cursor. The scale is clamped to
+/// `Config::MIN_ZOOM_SCALE..=Config::MAX_ZOOM_SCALE`.
+pub fn zoom_about(center: Vec2, scale: f32, anchor: Vec2, factor: f32) -> (Vec2, f32) {
+    let new_scale = (scale * factor).clamp(Config::MIN_ZOOM_SCALE, Config::MAX_ZOOM_SCALE);
+    let new_center = anchor - (anchor - center) * (new_scale / scale);
+    (new_center, new_scale)
+}
+
+/// Converts the cursor's window position to world coordinates through the
+/// world camera, whatever its pan and zoom.
+#[derive(SystemParam)]
+pub struct WorldCursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<WorldCamera>>,
+}
+
+impl WorldCursor<'_, '_> {
+    /// World position under the cursor, if it is over the window.
+    pub fn position(&self) -> Option<Vec2> {
+        let cursor = self.windows.single().ok()?.cursor_position()?;
+        let (camera, camera_transform) = self.cameras.single().ok()?;
+        camera.viewport_to_world_2d(camera_transform, cursor).ok()
+    }
+}
+
+fn setup_camera(mut commands: Commands, bounds: Res<WorldBounds>) {
+    commands.spawn((Camera2d, WorldCamera, world_projection(bounds.size())));
+}
+
+fn zoom_camera(
+    scroll: Res<AccumulatedMouseScroll>,
+    cursor: WorldCursor,
+    mut view: Query<(&mut Transform, &mut Projection), With<WorldCamera>>,
+) {
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / Config::SCROLL_PIXELS_PER_LINE,
+    };
+    if lines == 0.0 {
+        return;
+    }
+    let Ok((mut transform, mut projection)) = view.single_mut() else {
+        return;
+    };
+    let Projection::Orthographic(orthographic) = projection.as_mut() else {
+        return;
+    };
+
+    let center = transform.translation.truncate();
+    let anchor = cursor.position().unwrap_or(center);
+    // Scrolling up zooms in, i.e. shrinks the visible area
+    let factor = Config::ZOOM_STEP.powf(-lines);
+    let (center, scale) = zoom_about(center, orthographic.scale, anchor, factor);
+    orthographic.scale = scale;
+    transform.translation = center.extend(transform.translation.z);
+}
+
+fn pan_camera(
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor: WorldCursor,
+    mut view: Query<&mut Transform, With<WorldCamera>>,
+    mut grab: ResMut<PanGrab>,
+) {
+    if !mouse.pressed(MouseButton::Right) {
+        grab.0 = None;
+        return;
+    }
+    let Some(position) = cursor.position() else {
+        return;
+    };
+    let Some(grabbed) = grab.0 else {
+        grab.0 = Some(position);
+        return;
+    };
+
+    // Move the camera so the grabbed world point is back under the cursor
+    if let Ok(mut transform) = view.single_mut() {
+        transform.translation += (grabbed - position).extend(0.0);
+    }
+}
+
+fn fit_world(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut view: Query<(&mut Transform, &mut Projection), With<WorldCamera>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    if let Ok((mut transform, mut projection)) = view.single_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            orthographic.scale = 1.0;
+        }
+    }
+}
@@ -1,15 +1,37 @@
-use crate::boundary::Bounding;
+use crate::boundary::{Bounding, WorldBounds};
+use crate::simulation::SimulationSet;
 use crate::spatial_index::SpatialIndex;
 use bevy::prelude::*;
 use std::marker::PhantomData;
 
 pub struct CollisionPlugin<Hittable, Hitter> {
+    mode: CollisionMode,
     _phantom: PhantomData<(Hittable, Hitter)>,
 }
 
+/// How hitters are tested against hittables each tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CollisionMode {
+    /// Overlap at the hitter's current position only.
+    #[default]
+    Discrete,
+    /// The circle swept from the previous to the current position, so fast
+    /// hitters can't pass through a hittable between two ticks.
+    Swept,
+}
+
 impl<Hittable: Component, Hitter: Component> CollisionPlugin<Hittable, Hitter> {
     pub fn new() -> Self {
+        Self::with_mode(CollisionMode::Discrete)
+    }
+
+    pub fn swept() -> Self {
+        Self::with_mode(CollisionMode::Swept)
+    }
+
+    pub fn with_mode(mode: CollisionMode) -> Self {
         Self {
+            mode,
             _phantom: PhantomData,
         }
     }
@@ -19,9 +41,36 @@ impl<Hittable: Component, Hitter: Component> Plugin for CollisionPlugin<Hittable
     fn build(&self, app: &mut App) {
         app.init_resource::<SpatialIndex>()
             .add_message::<HitEvent<Hittable, Hitter>>()
-            .add_systems(Startup, initialize_hittables::<Hittable>)
-            .add_systems(Update, update_hittable_positions::<Hittable>)
-            .add_systems(Update, collision_system::<Hittable, Hitter>);
+            .add_systems(Startup, initialize_hittables::<Hittable>);
+
+        match self.mode {
+            CollisionMode::Discrete => {
+                app.add_systems(
+                    FixedUpdate,
+                    (
+                        update_hittable_positions::<Hittable>,
+                        collision_system::<Hittable, Hitter>,
+                    )
+                        .chain()
+                        .in_set(SimulationSet::Collision),
+                );
+            }
+            CollisionMode::Swept => {
+                app.add_systems(
+                    FixedUpdate,
+                    record_previous_positions::<Hitter>.in_set(SimulationSet::Tick),
+                )
+                .add_systems(
+                    FixedUpdate,
+                    (
+                        update_hittable_positions::<Hittable>,
+                        swept_collision_system::<Hittable, Hitter>,
+                    )
+                        .chain()
+                        .in_set(SimulationSet::Collision),
+                );
+            }
+        }
     }
 }
 
@@ -52,6 +101,10 @@ impl<A, B> HitEvent<A, B> {
 #[derive(Debug, Component)]
 pub struct Collidable;
 
+/// Where a hitter was at the start of the current tick, used by swept collision.
+#[derive(Debug, Component, Default, Clone, Copy, Deref, DerefMut)]
+pub struct PreviousPosition(pub Vec2);
+
 fn collision_system<A: Component, B: Component>(
     mut hits: MessageWriter<HitEvent<A, B>>,
     spatial_index: Res<SpatialIndex>,
@@ -86,6 +139,107 @@ fn collision_system<A: Component, B: Component>(
     }
 }
 
+fn record_previous_positions<B: Component>(
+    mut commands: Commands,
+    mut hitters: Query<(Entity, &Transform, Option<&mut PreviousPosition>), With<B>>,
+) {
+    for (entity, transform, previous) in hitters.iter_mut() {
+        let position = transform.translation.truncate();
+        match previous {
+            Some(mut previous) => previous.0 = position,
+            None => {
+                commands.entity(entity).insert(PreviousPosition(position));
+            }
+        }
+    }
+}
+
+type SweptHittables<'w, 's, A> =
+    Query<'w, 's, (Entity, &'static Transform, &'static Bounding), (With<Collidable>, With<A>)>;
+
+type SweptHitters<'w, 's, B> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Bounding,
+        Option<&'static PreviousPosition>,
+    ),
+    (With<Collidable>, With<B>),
+>;
+
+fn swept_collision_system<A: Component, B: Component>(
+    mut hits: MessageWriter<HitEvent<A, B>>,
+    spatial_index: Res<SpatialIndex>,
+    bounds: Option<Res<WorldBounds>>,
+    hittables: SweptHittables<A>,
+    hitters: SweptHitters<B>,
+) {
+    for (hitter_entity, hitter_transform, hitter_bounds, previous) in hitters.iter() {
+        let end = hitter_transform.translation.truncate();
+        let mut start = previous.map_or(end, |previous| previous.0);
+        // A path longer than half the world is a teleport, not a move
+        if bounds
+            .as_ref()
+            .is_some_and(|bounds| (end - start).abs().cmpgt(bounds.half_size()).any())
+        {
+            start = end;
+        }
+
+        // Earliest contact along the path; ties go to the lowest entity id
+        let mut earliest: Option<(f32, Entity)> = None;
+        for nearby_entity in spatial_index.get_along_segment(start, end) {
+            if let Ok((hittable_entity, hittable_transform, hittable_bounds)) =
+                hittables.get(nearby_entity)
+            {
+                let Some(t) = swept_hit_time(
+                    start,
+                    end,
+                    hittable_transform.translation.truncate(),
+                    **hittable_bounds + **hitter_bounds,
+                ) else {
+                    continue;
+                };
+                if earliest.is_none_or(|(best, _)| t < best) {
+                    earliest = Some((t, hittable_entity));
+                }
+            }
+        }
+
+        if let Some((_, hittable_entity)) = earliest {
+            hits.write(HitEvent {
+                entities: (hittable_entity, hitter_entity),
+                _phantom: PhantomData,
+            });
+        }
+    }
+}
+
+/// Fraction of the way from `start` to `end` at which a point first comes
+/// closer than `radius` to `center`, or `None` if it never does.
+pub fn swept_hit_time(start: Vec2, end: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let offset = start - center;
+    let c = offset.length_squared() - radius * radius;
+    if c < 0.0 {
+        return Some(0.0);
+    }
+
+    let direction = end - start;
+    let a = direction.length_squared();
+    if a <= f32::EPSILON {
+        return None;
+    }
+    let b = 2.0 * offset.dot(direction);
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
 pub fn initialize_hittables<Hittable: Component>(
     mut spatial_index: ResMut<SpatialIndex>,
     query: Query<(Entity, &Transform), With<Hittable>>,
@@ -0,0 +1,144 @@
+//! Colonies: every ant belongs to a colony with a nest it was spawned around.
+//! With `SimulationSettings::return_to_nest` on, laden ants head straight home
+//! and drop their food at the nest instead of on the next food item they hit.
+
+use bevy::prelude::*;
+
+use crate::config::{Config, SimulationSettings};
+use crate::net::{BackendEventWriter, BackendSimEvent};
+use crate::simulation::{SimRng, SimulationSet};
+use crate::{Ant, Cooldown, Food, GatherCounts, Velocity, ant_hits_system, gatherer_movement};
+use crate::{put_down_food, turn_around, turn_jitter};
+
+pub struct ColonyPlugin;
+
+impl Plugin for ColonyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            steer_home_system
+                .run_if(returns_to_nest)
+                .in_set(SimulationSet::Movement)
+                .before(gatherer_movement),
+        )
+        .add_systems(
+            FixedUpdate,
+            deliver_to_nest_system
+                .run_if(returns_to_nest)
+                .in_set(SimulationSet::Hits)
+                .after(ant_hits_system),
+        );
+    }
+}
+
+/// A colony's nest. Lives on the nest entity; its ants carry the matching `ColonyId`.
+#[derive(Debug, Component, Clone, Copy)]
+pub struct Colony {
+    pub id: u32,
+    pub nest: Vec2,
+    pub color: Color,
+}
+
+/// The colony an ant belongs to.
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ColonyId(pub u32);
+
+fn nest_of(colonies: &Query<&Colony>, id: ColonyId) -> Option<Vec2> {
+    colonies
+        .iter()
+        .find(|colony| colony.id == id.0)
+        .map(|colony| colony.nest)
+}
+
+fn returns_to_nest(settings: Res<SimulationSettings>) -> bool {
+    settings.return_to_nest
+}
+
+fn steer_home_system(
+    colonies: Query<&Colony>,
+    mut ants: Query<(&mut Velocity, &Transform, &ColonyId, &Children), With<Ant>>,
+) {
+    for (mut velocity, transform, colony_id, carrying) in &mut ants {
+        if carrying.is_empty() {
+            continue;
+        }
+        let Some(nest) = nest_of(&colonies, *colony_id) else {
+            continue;
+        };
+        let home = (nest - transform.translation.truncate()).normalize_or_zero();
+        if home != Vec2::ZERO {
+            velocity.0 = home;
+        }
+    }
+}
+
+/// Ants that may be carrying food home.
+type HomingAnts<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Velocity,
+        &'static mut GatherCounts,
+        &'static Transform,
+        &'static ColonyId,
+        &'static Children,
+    ),
+    (With<Ant>, Without<Cooldown>),
+>;
+
+fn deliver_to_nest_system(
+    colonies: Query<&Colony>,
+    settings: Res<SimulationSettings>,
+    mut rng: ResMut<SimRng>,
+    mut commands: Commands,
+    mut backend_events: BackendEventWriter,
+    mut ants: HomingAnts,
+    mut food_query: Query<&mut Transform, (With<Food>, Without<Ant>)>,
+) {
+    for (ant, mut velocity, mut counts, transform, colony_id, carrying) in &mut ants {
+        let Some(&carried_food) = carrying.first() else {
+            continue;
+        };
+        let Some(nest) = nest_of(&colonies, *colony_id) else {
+            continue;
+        };
+        if transform.translation.truncate().distance(nest) > Config::NEST_RADIUS {
+            continue;
+        }
+
+        put_down_food(
+            &mut commands,
+            ant,
+            carried_food,
+            transform.translation,
+            food_query.get_mut(carried_food).ok(),
+            settings.pickup_cooldown,
+        );
+        counts.drops += 1;
+        let jitter = turn_jitter(&mut rng, settings.turn_angle_range);
+        let new_direction = turn_around(&mut velocity, jitter);
+
+        let ant_id = backend_events.ant_id(ant);
+        let food_id = backend_events.food_id(carried_food);
+        backend_events.write(BackendSimEvent::FoodDrop {
+            ant_id,
+            colony_id: colony_id.0,
+            food_id,
+            x: transform.translation.x,
+            y: transform.translation.y,
+            direction_x: new_direction.x,
+            direction_y: new_direction.y,
+            frame: backend_events.frame(),
+        });
+        backend_events.write(BackendSimEvent::AntTurnMove {
+            ant_id,
+            colony_id: colony_id.0,
+            x: transform.translation.x,
+            y: transform.translation.y,
+            direction_x: new_direction.x,
+            direction_y: new_direction.y,
+            frame: backend_events.frame(),
+        });
+    }
+}
@@ -2,6 +2,7 @@
 //! Contains all configurable parameters in one place to avoid magic numbers throughout the codebase.
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
 /// Static configuration constants for the simulation.
 /// These values don't change at runtime for optimal performance.
@@ -14,22 +15,77 @@ impl Config {
     /// Size of food sprites
     pub const FOOD_SIZE: Vec2 = Vec2::new(10.0, 10.0);
 
-    /// Step size for ant spawning grid (controls ant density)
-    pub const ANT_SPAWN_STEP: i32 = 50;
+    /// Default world width per spawned ant (controls ant density)
+    pub const ANT_SPAWN_STEP: f32 = 50.0;
 
-    /// Y position where ants are spawned
-    pub const ANT_SPAWN_Y: f32 = 100.0;
+    /// Radius around its nest within which a colony's ants are spawned
+    pub const NEST_SPAWN_RADIUS: f32 = 60.0;
 
-    /// Number of food items to spawn
-    pub const FOOD_COUNT: i32 = 80;
+    /// Distance from the nest at which a returning ant drops its food
+    pub const NEST_RADIUS: f32 = 20.0;
 
-    /// Maximum random angle range when turning (in radians, ±90 degrees)
+    /// Size of nest sprites
+    pub const NEST_SIZE: Vec2 = Vec2::new(40.0, 40.0);
+
+    /// Default number of food items to spawn
+    pub const FOOD_COUNT: u32 = 80;
+
+    /// Default maximum random angle range when turning (in radians, ±90 degrees)
     pub const TURN_ANGLE_RANGE: f32 = std::f32::consts::FRAC_PI_2;
 
-    /// Cell size for spatial indexing collision detection
-    /// Should be larger than collision radius for optimal performance
+    /// Default cell size for spatial indexing collision detection
+    /// Must be at least twice the collision radius, as only adjacent cells are checked
     pub const SPATIAL_CELL_SIZE: f32 = 20.0;
 
+    /// World size used when neither a world size nor a window size is configured
+    pub const DEFAULT_WORLD_SIZE: Vec2 = Vec2::new(1280.0, 720.0);
+
+    /// Simulation ticks per second of sim time (the fixed timestep rate)
+    pub const SIM_TICK_HZ: f64 = 60.0;
+
+    /// Cell size of the pheromone grid
+    pub const PHEROMONE_CELL_SIZE: f32 = 10.0;
+
+    /// Default scent laid per second by an ant carrying food.
+    /// 0 keeps the pure random-walk baseline; scenarios turn pheromones on.
+    pub const PHEROMONE_DEPOSIT_RATE: f32 = 0.0;
+
+    /// Default fraction of scent that evaporates per second
+    pub const PHEROMONE_EVAPORATION_RATE: f32 = 0.1;
+
+    /// Default fraction of scent exchanged with neighbouring cells per second
+    pub const PHEROMONE_DIFFUSION_RATE: f32 = 0.5;
+
+    /// Default maximum turn rate towards stronger scent (in radians per second)
+    pub const PHEROMONE_STEERING_RATE: f32 = 1.5;
+
+    /// Gradients weaker than this are treated as no scent at all
+    pub const PHEROMONE_MIN_GRADIENT: f32 = 1e-4;
+
+    /// Default Deneubourg pick-up constant: (k1 / (k1 + f))^2
+    pub const DENEUBOURG_K1: f32 = 0.1;
+
+    /// Default Deneubourg drop constant: (f / (k2 + f))^2
+    pub const DENEUBOURG_K2: f32 = 0.3;
+
+    /// Food items in the neighbouring spatial cells that count as full density (f = 1)
+    pub const FOOD_DENSITY_SATURATION: f32 = 8.0;
+
+    /// Grid cell size for the occupied-cell clustering metric (the backend's default)
+    pub const ANALYTICS_CELL_SIZE: f32 = 50.0;
+
+    /// Frames between refreshes of the live clustering metrics
+    pub const ANALYTICS_UPDATE_FRAMES: u32 = 30;
+
+    /// Refreshes kept for the clustering charts
+    pub const ANALYTICS_HISTORY_LEN: usize = 120;
+
+    /// Thickness of the clustering chart lines (in pixels)
+    pub const CHART_LINE_WIDTH: f32 = 2.0;
+
+    /// Z-layer for nests
+    pub const NEST_Z_LAYER: f32 = 0.5;
+
     /// Z-layer for ants
     pub const ANT_Z_LAYER: f32 = 2.0;
 
@@ -39,14 +95,36 @@ impl Config {
     /// Z-layer for carried food (should be above ants)
     pub const CARRIED_FOOD_Z_LAYER: f32 = 3.0;
 
+    /// Z-layer for the food density heatmap (over food, under ants)
+    pub const HEATMAP_Z_LAYER: f32 = 1.5;
+
+    /// Alpha of the heatmap cell holding the most loose food
+    pub const HEATMAP_MAX_ALPHA: f32 = 0.6;
+
+    /// Z-layer for the drag-to-erase selection box (above everything)
+    pub const SELECTION_Z_LAYER: f32 = 4.0;
+
+    /// Drags shorter than this (in world units) count as clicks
+    pub const DRAG_THRESHOLD: f32 = 5.0;
+
+    /// Projection scale range of the world camera (1.0 fits the whole world)
+    pub const MIN_ZOOM_SCALE: f32 = 0.05;
+    pub const MAX_ZOOM_SCALE: f32 = 2.0;
+
+    /// Projection scale factor per mouse-wheel line
+    pub const ZOOM_STEP: f32 = 1.1;
+
+    /// Pixel-based scroll deltas (touchpads, browsers) per mouse-wheel line
+    pub const SCROLL_PIXELS_PER_LINE: f32 = 100.0;
+
     /// Base speed multiplier for ant movement (used as default)
     pub const BASE_ANT_SPEED: f32 = 100.0;
 
-    /// Base cooldown time after picking up/dropping food (in seconds)
+    /// Default cooldown time after picking up/dropping food (in seconds)
     /// Calibrated for BASE_ANT_SPEED to ensure ants move away from dropped food
     pub const BASE_PICKUP_COOLDOWN: f32 = 1.0;
 
-    /// Base radius for bounding collision detection
+    /// Default radius for bounding collision detection
     pub const BASE_COLLISION_RADIUS: f32 = 10.0;
 
     /// Speed multiplier range for UI control
@@ -55,64 +133,145 @@ impl Config {
 
     /// Special unlimited speed value (negative indicates unlimited)
     pub const UNLIMITED_SPEED: f32 = -1.0;
+
+    /// Time scale used for unlimited speed: sim seconds per real second
+    pub const UNLIMITED_TIME_SCALE: f32 = Self::MAX_SPEED_MULTIPLIER * 20.0;
+
+    /// Real-time window over which the achieved tick rate is measured (in seconds)
+    pub const TICK_RATE_WINDOW_SECS: f32 = 0.5;
+
+    /// Ticks run by the "run N ticks" control before pausing again
+    pub const RUN_TICKS_STEP: u64 = 100;
+
+    /// Real seconds between `sim_heartbeat` messages to the backend
+    pub const BACKEND_HEARTBEAT_SECS: f32 = 5.0;
+
+    /// Most outbound backend messages buffered while the websocket is down
+    pub const BACKEND_OUTBOUND_CAPACITY: usize = 4096;
+
+    /// First and longest real-time wait before reconnecting to the backend (in seconds)
+    pub const BACKEND_RECONNECT_MIN_SECS: f32 = 0.5;
+    pub const BACKEND_RECONNECT_MAX_SECS: f32 = 30.0;
 }
 
 /// Runtime configuration resource that can be modified during gameplay
-#[derive(Resource)]
+#[derive(Resource, Clone, Debug)]
 pub struct SimulationSettings {
-    /// Current speed multiplier (1.0 = base speed, 10.0 = max speed)
+    /// Current speed multiplier (1.0 = base speed, 10.0 = max speed).
+    /// Scales how many fixed ticks run per real second.
     pub speed_multiplier: f32,
+    /// Scent laid per second by an ant carrying food. 0 disables pheromones,
+    /// leaving the pure random-walk baseline.
+    pub pheromone_deposit_rate: f32,
+    /// Fraction of scent lost per second
+    pub pheromone_evaporation_rate: f32,
+    /// Fraction of scent shared with neighbouring cells per second
+    pub pheromone_diffusion_rate: f32,
+    /// Maximum turn rate of unladen ants towards stronger scent (radians per second)
+    pub pheromone_steering_rate: f32,
+    /// How an ant decides to pick up or drop food on contact
+    pub pickup_rule: PickupRule,
+    /// Deneubourg pick-up constant k1
+    pub deneubourg_k1: f32,
+    /// Deneubourg drop constant k2
+    pub deneubourg_k2: f32,
+    /// Number of colonies the ants are split into
+    pub colony_count: u32,
+    /// Laden ants head straight home and drop their food at the nest
+    pub return_to_nest: bool,
+    /// Number of food items spawned
+    pub food_count: u32,
+    /// World width per spawned ant
+    pub ant_spawn_step: f32,
+    /// Maximum random deviation when an ant turns around (radians)
+    pub turn_angle_range: f32,
+    /// Cooldown after picking up or dropping food (seconds)
+    pub pickup_cooldown: f32,
+    /// Bounding radius of ants and food
+    pub base_collision_radius: f32,
+    /// Cell size of the collision spatial index
+    pub spatial_cell_size: f32,
+    /// Cell size of the food density heatmap overlay
+    pub heatmap_cell_size: f32,
+}
+
+/// Decision rule applied when an ant touches a food item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PickupRule {
+    /// Always pick up on the first contact and drop on the next one.
+    #[default]
+    Always,
+    /// Pick up with probability (k1/(k1+f))^2 and drop with (f/(k2+f))^2,
+    /// where f is the local food density.
+    Deneubourg,
 }
 
 impl Default for SimulationSettings {
     fn default() -> Self {
         Self {
             speed_multiplier: 5.0,
+            pheromone_deposit_rate: Config::PHEROMONE_DEPOSIT_RATE,
+            pheromone_evaporation_rate: Config::PHEROMONE_EVAPORATION_RATE,
+            pheromone_diffusion_rate: Config::PHEROMONE_DIFFUSION_RATE,
+            pheromone_steering_rate: Config::PHEROMONE_STEERING_RATE,
+            pickup_rule: PickupRule::default(),
+            deneubourg_k1: Config::DENEUBOURG_K1,
+            deneubourg_k2: Config::DENEUBOURG_K2,
+            colony_count: 1,
+            return_to_nest: false,
+            food_count: Config::FOOD_COUNT,
+            ant_spawn_step: Config::ANT_SPAWN_STEP,
+            turn_angle_range: Config::TURN_ANGLE_RANGE,
+            pickup_cooldown: Config::BASE_PICKUP_COOLDOWN,
+            base_collision_radius: Config::BASE_COLLISION_RADIUS,
+            spatial_cell_size: Config::SPATIAL_CELL_SIZE,
+            heatmap_cell_size: Config::ANALYTICS_CELL_SIZE,
         }
     }
 }
 
 impl SimulationSettings {
-    /// Nominal ant speed in units/second before anti-tunneling capping.
-    pub fn ant_speed(&self) -> f32 {
+    pub fn is_unlimited_speed(&self) -> bool {
+        self.speed_multiplier == Config::UNLIMITED_SPEED
+    }
+
+    /// Sim seconds simulated per real second. Applied as the relative speed of
+    /// virtual time, so a higher multiplier runs more fixed ticks per frame
+    /// instead of taking longer steps.
+    pub fn time_scale(&self) -> f32 {
         if self.is_unlimited_speed() {
-            Config::BASE_ANT_SPEED * Config::MAX_SPEED_MULTIPLIER * 20.0
+            Config::UNLIMITED_TIME_SCALE
         } else {
-            Config::BASE_ANT_SPEED * self.speed_multiplier
+            self.speed_multiplier
         }
     }
 
-    pub fn is_unlimited_speed(&self) -> bool {
-        self.speed_multiplier == Config::UNLIMITED_SPEED
-    }
-
     pub fn collision_radius(&self) -> f32 {
-        Config::BASE_COLLISION_RADIUS
+        self.base_collision_radius
     }
 
-    /// Maximum safe displacement per frame to prevent tunneling.
+    /// Maximum safe displacement per tick to prevent tunneling.
     /// Derived from collision geometry: must be smaller than the sum of
     /// ant + food radii so the discrete overlap check never misses.
     pub fn safe_step_distance(&self) -> f32 {
         self.collision_radius() * 2.0 * 0.9
     }
 
-    /// Effective speed in units/sec after accounting for the anti-tunneling cap.
-    /// For unlimited mode this is the fastest collision-safe speed for the
-    /// current frame duration; for normal modes it equals the nominal speed
-    /// unless the frame is so slow it would cause tunneling.
-    pub fn effective_speed(&self, delta_secs: f32) -> f32 {
-        if delta_secs <= f32::EPSILON {
-            return 0.0;
+    /// Chance that an unladen ant picks up food where the local density is `f`.
+    pub fn pickup_probability(&self, f: f32) -> f32 {
+        match self.pickup_rule {
+            PickupRule::Always => 1.0,
+            PickupRule::Deneubourg => (self.deneubourg_k1 / (self.deneubourg_k1 + f)).powi(2),
         }
-        let nominal = self.ant_speed();
-        let max_safe = self.safe_step_distance() / delta_secs;
-        nominal.min(max_safe)
     }
 
-    /// Effective speed expressed as a multiplier of BASE_ANT_SPEED.
-    pub fn effective_multiplier(&self, delta_secs: f32) -> f32 {
-        self.effective_speed(delta_secs) / Config::BASE_ANT_SPEED
+    /// Chance that a laden ant drops its food where the local density is `f`.
+    pub fn drop_probability(&self, f: f32) -> f32 {
+        match self.pickup_rule {
+            PickupRule::Always => 1.0,
+            PickupRule::Deneubourg => (f / (self.deneubourg_k2 + f)).powi(2),
+        }
     }
 }
 
@@ -126,6 +285,25 @@ impl Colors {
     /// Color for food sprites (dark red)
     pub const FOOD: Color = Color::srgb(192.0 / 255.0, 2.0 / 255.0, 2.0 / 255.0);
 
+    /// Colony colors for ants and nests, by colony id (wrapping)
+    pub const COLONIES: [Color; 4] = [
+        Self::ANT,
+        Color::srgb(0.95, 0.85, 0.3),
+        Color::srgb(0.4, 0.85, 0.45),
+        Color::srgb(0.75, 0.5, 0.9),
+    ];
+
+    /// Heatmap cell color; its alpha scales with the cell's food count
+    pub const HEATMAP: Color = Color::srgb(1.0, 0.55, 0.0);
+
+    /// Fill of the drag-to-erase selection box (translucent white)
+    pub const SELECTION: Color = Color::srgba(1.0, 1.0, 1.0, 0.2);
+
     /// Background color (blue)
     pub const BACKGROUND: Color = Color::srgb(95.0 / 255.0, 151.0 / 255.0, 212.0 / 255.0);
+
+    /// Color of the given colony's ants and nest
+    pub fn colony(id: u32) -> Color {
+        Self::COLONIES[id as usize % Self::COLONIES.len()]
+    }
 }
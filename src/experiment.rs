@@ -0,0 +1,337 @@
+//! Headless batch experiments: run the simulation for a fixed tick budget over
+//! seeds × parameter combinations and report the clustering metrics of each
+//! run, one CSV row per run. Driven by the `gatherers-batch` binary.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::analytics::{ClusteringSummary, clustering_summary};
+use crate::runtime::{parse_bool, parse_f32, parse_size, parse_u32, parse_u64};
+use crate::{
+    Ant, BoundaryPlugin, CollisionPlugin, ColonyPlugin, Config, Food, PheromonePlugin, PickupRule,
+    Scenario, SimRng, SimTick, SimulationPlugin, SimulationSettings, SpatialIndex, WorldBounds,
+    spawn_world,
+};
+
+/// Command-line help for the gatherers-batch binary.
+pub const BATCH_USAGE: &str = "\
+Usage: gatherers-batch [OPTIONS]
+
+Runs the simulation headlessly for every seed and parameter combination and
+writes one CSV row per run with the final clustering metrics.
+
+      --seeds <LIST>         Seeds as a list and/or ranges, e.g. 1-10 or 1,5,9 (default 1-10)
+      --ticks <N>            Simulation ticks per run (default 3600)
+      --world-size <WxH>     World size (default 1280x720)
+      --scenario <PATH>      Scenario file applied before the sweep (TOML or JSON)
+      --sweep <NAME=V1,V2>   Parameter values to sweep; repeat for a full cross product
+      --out <PATH>           Write the CSV to a file instead of stdout
+  -h, --help                 Print this help
+
+Sweepable parameters: food_count, ant_spawn_step, turn_angle_range, pickup_cooldown,
+collision_radius, spatial_cell_size, pheromone_deposit_rate, pheromone_evaporation_rate,
+pheromone_diffusion_rate, pheromone_steering_rate, pickup_rule (always|deneubourg),
+deneubourg_k1 (k1), deneubourg_k2 (k2), colonies, return_to_nest
+";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchConfig {
+    pub seeds: Vec<u64>,
+    pub ticks: u64,
+    pub world_size: Vec2,
+    pub scenario: Scenario,
+    pub sweeps: Vec<ParameterSweep>,
+    pub output: Option<PathBuf>,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            seeds: (1..=10).collect(),
+            ticks: 3600,
+            world_size: Config::DEFAULT_WORLD_SIZE,
+            scenario: Scenario::default(),
+            sweeps: Vec::new(),
+            output: None,
+        }
+    }
+}
+
+impl BatchConfig {
+    pub fn from_args<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut config = Self::default();
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(flag) = args.next() {
+            if matches!(flag.as_str(), "--help" | "-h") {
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{flag} requires a value"))?;
+            match flag.as_str() {
+                "--seeds" => config.seeds = parse_seeds(&value)?,
+                "--ticks" => config.ticks = parse_u64("--ticks", &value)?,
+                "--world-size" => {
+                    let (width, height) = parse_size("--world-size", &value)?;
+                    config.world_size = Vec2::new(width as f32, height as f32);
+                }
+                "--scenario" => config.scenario = Scenario::load(&value)?,
+                "--sweep" => config.sweeps.push(ParameterSweep::parse(&value)?),
+                "--out" => config.output = Some(PathBuf::from(value)),
+                _ => return Err(format!("unknown argument {flag:?}")),
+            }
+        }
+
+        // Reject bad combinations before spending time on any run
+        for combination in config.combinations() {
+            config.settings_for(&combination)?;
+        }
+        Ok(config)
+    }
+
+    /// Every combination of the swept values, as `(name, value)` pairs in
+    /// sweep order. A batch without sweeps has one empty combination.
+    pub fn combinations(&self) -> Vec<Vec<(String, String)>> {
+        self.sweeps
+            .iter()
+            .fold(vec![Vec::new()], |combinations, sweep| {
+                combinations
+                    .iter()
+                    .flat_map(|combination| {
+                        sweep.values.iter().map(move |value| {
+                            let mut combination = combination.clone();
+                            combination.push((sweep.name.clone(), value.clone()));
+                            combination
+                        })
+                    })
+                    .collect()
+            })
+    }
+
+    /// Default settings with the scenario and then the combination applied.
+    pub fn settings_for(
+        &self,
+        combination: &[(String, String)],
+    ) -> Result<SimulationSettings, String> {
+        let mut settings = SimulationSettings::default();
+        self.scenario.apply(&mut settings);
+        for (name, value) in combination {
+            set_parameter(&mut settings, name, value)?;
+        }
+        Scenario::from_settings(&settings).validate()?;
+        Ok(settings)
+    }
+
+    pub fn csv_header(&self) -> String {
+        let mut columns = vec!["seed".to_string()];
+        columns.extend(self.sweeps.iter().map(|sweep| sweep.name.clone()));
+        columns.extend(
+            [
+                "ticks",
+                "loose_food_count",
+                "occupied_cell_count",
+                "nearest_neighbor_mean_distance",
+            ]
+            .map(String::from),
+        );
+        columns.join(",")
+    }
+
+    /// Runs every combination for every seed, handing each row to `on_row`
+    /// as soon as its run finishes.
+    pub fn run(&self, mut on_row: impl FnMut(&BatchRow)) -> Result<(), String> {
+        for combination in self.combinations() {
+            let settings = self.settings_for(&combination)?;
+            for &seed in &self.seeds {
+                let summary = run_headless(&ExperimentRun {
+                    seed,
+                    ticks: self.ticks,
+                    world_size: self.world_size,
+                    settings: settings.clone(),
+                });
+                on_row(&BatchRow {
+                    seed,
+                    parameters: combination.iter().map(|(_, value)| value.clone()).collect(),
+                    ticks: self.ticks,
+                    summary,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Values to try for one parameter, parsed from `name=v1,v2,...`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterSweep {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl ParameterSweep {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, values) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("--sweep must be NAME=V1,V2,..., got {spec:?}"))?;
+        let values: Vec<String> = values
+            .split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+        if values.is_empty() {
+            return Err(format!("--sweep {name} needs at least one value"));
+        }
+        // Parse every value now so typos are reported up front
+        let mut settings = SimulationSettings::default();
+        for value in &values {
+            set_parameter(&mut settings, name, value)?;
+        }
+        Ok(Self {
+            name: name.to_string(),
+            values,
+        })
+    }
+}
+
+/// Sets one named simulation parameter from its text value.
+pub fn set_parameter(
+    settings: &mut SimulationSettings,
+    name: &str,
+    value: &str,
+) -> Result<(), String> {
+    match name {
+        "food_count" => settings.food_count = parse_u32(name, value)?,
+        "ant_spawn_step" => settings.ant_spawn_step = parse_f32(name, value)?,
+        "turn_angle_range" => settings.turn_angle_range = parse_f32(name, value)?,
+        "pickup_cooldown" => settings.pickup_cooldown = parse_f32(name, value)?,
+        "collision_radius" => settings.base_collision_radius = parse_f32(name, value)?,
+        "spatial_cell_size" => settings.spatial_cell_size = parse_f32(name, value)?,
+        "pheromone_deposit_rate" => settings.pheromone_deposit_rate = parse_f32(name, value)?,
+        "pheromone_evaporation_rate" => {
+            settings.pheromone_evaporation_rate = parse_f32(name, value)?;
+        }
+        "pheromone_diffusion_rate" => settings.pheromone_diffusion_rate = parse_f32(name, value)?,
+        "pheromone_steering_rate" => settings.pheromone_steering_rate = parse_f32(name, value)?,
+        "pickup_rule" => {
+            settings.pickup_rule = match value {
+                "always" => PickupRule::Always,
+                "deneubourg" => PickupRule::Deneubourg,
+                _ => {
+                    return Err(format!(
+                        "pickup_rule must be always or deneubourg, got {value:?}"
+                    ));
+                }
+            }
+        }
+        "deneubourg_k1" | "k1" => settings.deneubourg_k1 = parse_f32(name, value)?,
+        "deneubourg_k2" | "k2" => settings.deneubourg_k2 = parse_f32(name, value)?,
+        "colonies" => {
+            settings.colony_count = parse_u32(name, value)?;
+            if settings.colony_count == 0 {
+                return Err("colonies must be at least 1".to_string());
+            }
+        }
+        "return_to_nest" => settings.return_to_nest = parse_bool(name, value)?,
+        _ => return Err(format!("unknown parameter {name:?}")),
+    }
+    Ok(())
+}
+
+/// Parses seeds like `1-10`, `3,7,9` or `1-3,10`.
+fn parse_seeds(value: &str) -> Result<Vec<u64>, String> {
+    let mut seeds = Vec::new();
+    for part in value.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let first = parse_u64("--seeds", first)?;
+                let last = parse_u64("--seeds", last)?;
+                if first > last {
+                    return Err(format!("--seeds range {part:?} is empty"));
+                }
+                seeds.extend(first..=last);
+            }
+            None => seeds.push(parse_u64("--seeds", part)?),
+        }
+    }
+    Ok(seeds)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchRow {
+    pub seed: u64,
+    /// Values of the swept parameters, in sweep order
+    pub parameters: Vec<String>,
+    pub ticks: u64,
+    pub summary: ClusteringSummary,
+}
+
+impl BatchRow {
+    pub fn to_csv(&self) -> String {
+        let mut columns = vec![self.seed.to_string()];
+        columns.extend(self.parameters.iter().cloned());
+        columns.push(self.ticks.to_string());
+        columns.push(self.summary.loose_food_count.to_string());
+        columns.push(self.summary.occupied_cell_count.to_string());
+        columns.push(self.summary.nearest_neighbor_mean_distance.to_string());
+        columns.join(",")
+    }
+}
+
+/// One headless run.
+#[derive(Clone, Debug)]
+pub struct ExperimentRun {
+    pub seed: u64,
+    pub ticks: u64,
+    pub world_size: Vec2,
+    pub settings: SimulationSettings,
+}
+
+/// Runs the full simulation without a window or backend for exactly
+/// `run.ticks` fixed ticks and returns the clustering of the loose food.
+pub fn run_headless(run: &ExperimentRun) -> ClusteringSummary {
+    // One fixed tick per update, so the run stops exactly at the budget
+    let settings = SimulationSettings {
+        speed_multiplier: 1.0,
+        ..run.settings.clone()
+    };
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+        .insert_resource(WorldBounds::new(run.world_size))
+        .insert_resource(SimRng::from_seed(Some(run.seed)))
+        .insert_resource(SpatialIndex::new(settings.spatial_cell_size))
+        .insert_resource(settings.clone())
+        .add_plugins(SimulationPlugin)
+        .add_plugins(BoundaryPlugin)
+        .add_plugins(PheromonePlugin)
+        .add_plugins(ColonyPlugin)
+        .add_plugins(CollisionPlugin::<Food, Ant>::new());
+
+    let world = app.world_mut();
+    spawn_world(
+        &mut world.commands(),
+        run.world_size,
+        &settings,
+        Some(run.seed),
+    );
+    world.flush();
+
+    while app.world().resource::<SimTick>().0 < run.ticks {
+        app.update();
+    }
+
+    let world = app.world_mut();
+    let positions: Vec<Vec2> = world
+        .query_filtered::<&Transform, (With<Food>, Without<ChildOf>)>()
+        .iter(world)
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    clustering_summary(&positions, Config::ANALYTICS_CELL_SIZE)
+}
@@ -0,0 +1,118 @@
+//! Toggleable overlay colouring a world grid by loose food per cell, so piles
+//! stay readable when the 10px food sprites are too small to see. The grid is
+//! aligned like the occupied-cell clustering metric, with the same default
+//! cell size as the backend's analytics.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::Food;
+use crate::config::{Colors, Config, SimulationSettings};
+
+pub struct HeatmapPlugin;
+
+impl Plugin for HeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeatmapOverlay>()
+            .add_systems(Startup, setup_heatmap)
+            .add_systems(Update, (toggle_heatmap, update_heatmap).chain());
+    }
+}
+
+/// Whether the heatmap overlay is shown. Toggled with `H`.
+#[derive(Resource, Default, Debug)]
+pub struct HeatmapOverlay {
+    pub visible: bool,
+}
+
+/// Parent of the heatmap cell sprites.
+#[derive(Component)]
+pub struct HeatmapRoot;
+
+/// Number of positions in each non-empty `cell_size` grid cell.
+pub fn food_counts_per_cell(
+    positions: impl IntoIterator<Item = Vec2>,
+    cell_size: f32,
+) -> HashMap<IVec2, u32> {
+    let mut counts = HashMap::new();
+    for position in positions {
+        let cell = (position / cell_size).floor().as_ivec2();
+        *counts.entry(cell).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn setup_heatmap(mut commands: Commands) {
+    commands.spawn((
+        HeatmapRoot,
+        Transform::from_xyz(0.0, 0.0, Config::HEATMAP_Z_LAYER),
+        Visibility::Hidden,
+    ));
+}
+
+fn toggle_heatmap(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<HeatmapOverlay>,
+    mut root: Query<&mut Visibility, With<HeatmapRoot>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        overlay.visible = !overlay.visible;
+    }
+    if overlay.is_changed()
+        && let Ok(mut visibility) = root.single_mut()
+    {
+        *visibility = if overlay.visible {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_heatmap(
+    mut frame: Local<u32>,
+    mut commands: Commands,
+    overlay: Res<HeatmapOverlay>,
+    settings: Res<SimulationSettings>,
+    root: Query<Entity, With<HeatmapRoot>>,
+    loose_food: Query<&Transform, (With<Food>, Without<ChildOf>)>,
+) {
+    let due = frame.is_multiple_of(Config::ANALYTICS_UPDATE_FRAMES);
+    *frame = frame.wrapping_add(1);
+    if !overlay.visible || !(due || overlay.is_changed()) {
+        return;
+    }
+    let Ok(root) = root.single() else {
+        return;
+    };
+
+    let cell_size = settings.heatmap_cell_size;
+    let counts = food_counts_per_cell(
+        loose_food
+            .iter()
+            .map(|transform| transform.translation.truncate()),
+        cell_size,
+    );
+    let max_count = counts.values().copied().max().unwrap_or(1) as f32;
+    // One pixel short of the cell size leaves visible grid lines, where the
+    // cells are big enough to spare it
+    let sprite_size = (cell_size - 1.0).max(cell_size / 2.0);
+
+    commands
+        .entity(root)
+        .despawn_children()
+        .with_children(|parent| {
+            for (cell, count) in counts {
+                let alpha = Config::HEATMAP_MAX_ALPHA * count as f32 / max_count;
+                parent.spawn((
+                    Sprite {
+                        color: Colors::HEATMAP.with_alpha(alpha),
+                        custom_size: Some(Vec2::splat(sprite_size)),
+                        ..default()
+                    },
+                    Transform::from_translation(((cell.as_vec2() + 0.5) * cell_size).extend(0.0)),
+                ));
+            }
+        });
+}
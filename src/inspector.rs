@@ -0,0 +1,218 @@
+//! Entity inspector: clicking an ant or food item shows its ids, position,
+//! motion and pickup state in a panel, for debugging a single entity without
+//! println tracing. `Esc` closes the panel.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::colony::ColonyId;
+use crate::config::Config;
+use crate::interaction::LooseFood;
+use crate::net::{BackendAntSlots, BackendFoodSlots};
+use crate::{Ant, Cooldown, Food, GatherCounts, Velocity};
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inspected>()
+            .add_systems(Startup, setup_inspector_panel)
+            .add_systems(Update, (close_inspector, update_inspector_panel).chain());
+    }
+}
+
+/// The entity shown in the inspector panel, if any.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Inspected(pub Option<Entity>);
+
+#[derive(Component)]
+pub struct InspectorPanel;
+
+#[derive(Component)]
+pub struct InspectorLabel;
+
+/// Ants and loose food a click can select for inspection.
+#[derive(SystemParam)]
+pub struct ClickTargets<'w, 's> {
+    ants: Query<'w, 's, (Entity, &'static Transform), With<Ant>>,
+    loose_food: LooseFood<'w, 's>,
+    inspected: ResMut<'w, Inspected>,
+}
+
+impl ClickTargets<'_, '_> {
+    /// Inspects the ant or loose food under `position`. Returns whether
+    /// anything was hit.
+    pub fn select_at(&mut self, position: Vec2) -> bool {
+        let ant_radius = Config::ANT_SIZE.max_element() / 2.0;
+        let food_radius = Config::FOOD_SIZE.max_element() / 2.0;
+        let candidates = self
+            .ants
+            .iter()
+            .map(|(entity, transform)| (entity, transform, ant_radius))
+            .chain(
+                self.loose_food
+                    .iter()
+                    .map(|(entity, transform)| (entity, transform, food_radius)),
+            )
+            .map(|(entity, transform, radius)| (entity, transform.translation.truncate(), radius));
+
+        let hit = entity_at(position, candidates);
+        if hit.is_some() {
+            self.inspected.0 = hit;
+        }
+        hit.is_some()
+    }
+}
+
+/// The candidate closest to `position` among those whose radius covers it.
+pub fn entity_at(
+    position: Vec2,
+    candidates: impl IntoIterator<Item = (Entity, Vec2, f32)>,
+) -> Option<Entity> {
+    candidates
+        .into_iter()
+        .map(|(entity, at, radius)| (entity, at.distance(position), radius))
+        .filter(|(_, distance, radius)| distance <= radius)
+        .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+        .map(|(entity, _, _)| entity)
+}
+
+/// Inspector panel on the right, hidden until something is selected.
+pub fn setup_inspector_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            InspectorPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                width: Val::Px(280.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(15.0)),
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            Interaction::default(),
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Inspector  [Esc] Close"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent.spawn((
+                InspectorLabel,
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+        });
+}
+
+fn close_inspector(keyboard_input: Res<ButtonInput<KeyCode>>, mut inspected: ResMut<Inspected>) {
+    if keyboard_input.just_pressed(KeyCode::Escape) && inspected.0.is_some() {
+        inspected.0 = None;
+    }
+}
+
+type InspectedAnts<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GlobalTransform,
+        &'static Velocity,
+        &'static GatherCounts,
+        Option<&'static ColonyId>,
+        Option<&'static Children>,
+        Option<&'static Cooldown>,
+    ),
+    With<Ant>,
+>;
+
+fn update_inspector_panel(
+    mut inspected: ResMut<Inspected>,
+    food_slots: Option<Res<BackendFoodSlots>>,
+    ant_slots: Option<Res<BackendAntSlots>>,
+    ants: InspectedAnts,
+    food: Query<(&GlobalTransform, Option<&ChildOf>), With<Food>>,
+    mut panel: Query<&mut Visibility, With<InspectorPanel>>,
+    mut label: Query<&mut Text, With<InspectorLabel>>,
+) {
+    let food_id = |entity: Entity| {
+        food_slots
+            .as_ref()
+            .and_then(|slots| slots.slot_for(entity))
+            .map_or_else(|| "none".to_string(), |slot| slot.to_string())
+    };
+
+    let ant_id = |entity: Entity| {
+        ant_slots
+            .as_ref()
+            .and_then(|slots| slots.slot_for(entity))
+            .unwrap_or(entity.to_bits() as usize)
+    };
+
+    let description = inspected.0.and_then(|entity| {
+        if let Ok((transform, velocity, counts, colony, children, cooldown)) = ants.get(entity) {
+            let position = transform.translation().truncate();
+            let carrying = children
+                .and_then(|children| children.iter().find(|child| food.contains(*child)))
+                .map_or_else(|| "nothing".to_string(), |carried| format!("food {}", food_id(carried)));
+            let cooldown = cooldown.map_or_else(
+                || "none".to_string(),
+                |cooldown| format!("{:.2} s", cooldown.timer),
+            );
+            Some(format!(
+                "Ant {entity}\nbackend id: {}\ncolony: {}\nposition: ({:.1}, {:.1})\nvelocity: ({:.2}, {:.2})\ncarrying: {carrying}\ncooldown: {cooldown}\npickups: {}  drops: {}",
+                ant_id(entity),
+                colony.map_or(0, |colony| colony.0),
+                position.x,
+                position.y,
+                velocity.0.x,
+                velocity.0.y,
+                counts.pickups,
+                counts.drops,
+            ))
+        } else if let Ok((transform, carrier)) = food.get(entity) {
+            let position = transform.translation().truncate();
+            let carried_by = carrier.map_or_else(
+                || "on the ground".to_string(),
+                |carrier| format!("ant {}", ant_id(carrier.parent())),
+            );
+            Some(format!(
+                "Food {entity}\nbackend id: {}\nposition: ({:.1}, {:.1})\ncarried by: {carried_by}",
+                food_id(entity),
+                position.x,
+                position.y,
+            ))
+        } else {
+            None
+        }
+    });
+
+    // The inspected entity may have been erased
+    if description.is_none() && inspected.0.is_some() {
+        inspected.0 = None;
+    }
+
+    if let Ok(mut visibility) = panel.single_mut() {
+        let wanted = if description.is_some() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(wanted);
+    }
+    if let (Some(description), Ok(mut text)) = (description, label.single_mut()) {
+        text.0 = description;
+    }
+}
@@ -0,0 +1,265 @@
+//! Mouse tools for setting up experiments by hand: click drops a food item,
+//! shift-click spawns an ant of the nearest nest's colony heading in a random
+//! direction, and dragging a rectangle deletes the loose food and ants inside it.
+//!
+//! The mouse systems only turn input into `EditCommand`s; `apply_edit_commands`
+//! carries them out, so tests and other plugins can edit the world the same way.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::boundary::{BoundaryWrap, Bounding};
+use crate::camera::WorldCursor;
+use crate::collision::Collidable;
+use crate::colony::{Colony, ColonyId};
+use crate::config::{Colors, Config, SimulationSettings};
+use crate::inspector::{ClickTargets, InspectorPlugin};
+use crate::net::{BackendEventWriter, BackendSimEvent};
+use crate::spatial_index::SpatialIndex;
+use crate::{Ant, Food, Velocity};
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InspectorPlugin)
+            .init_resource::<DragSelection>()
+            .add_message::<EditCommand>()
+            .add_systems(Startup, setup_selection_box)
+            .add_systems(
+                Update,
+                (
+                    handle_mouse_input,
+                    update_selection_box,
+                    apply_edit_commands,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// An edit to the running simulation, in world coordinates.
+#[derive(Message, Clone, Debug, PartialEq)]
+pub enum EditCommand {
+    PlaceFood(Vec2),
+    SpawnAnt(Vec2),
+    /// Deletes the loose food and ants inside the rectangle
+    Erase(Rect),
+}
+
+/// World position where the current left-button drag started, and where the
+/// cursor is now.
+#[derive(Resource, Default, Debug)]
+pub struct DragSelection {
+    start: Option<Vec2>,
+    current: Vec2,
+}
+
+impl DragSelection {
+    /// The dragged rectangle, once it is large enough not to be a click.
+    pub fn rect(&self) -> Option<Rect> {
+        let start = self.start?;
+        let rect = Rect::from_corners(start, self.current);
+        (rect.width().max(rect.height()) >= Config::DRAG_THRESHOLD).then_some(rect)
+    }
+}
+
+#[derive(Component)]
+pub struct SelectionBox;
+
+fn setup_selection_box(mut commands: Commands) {
+    commands.spawn((
+        SelectionBox,
+        Sprite {
+            color: Colors::SELECTION,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, Config::SELECTION_Z_LAYER),
+        Visibility::Hidden,
+    ));
+}
+
+fn handle_mouse_input(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cursor: WorldCursor,
+    ui_interactions: Query<&Interaction>,
+    mut drag: ResMut<DragSelection>,
+    mut edits: MessageWriter<EditCommand>,
+    mut targets: ClickTargets,
+) {
+    let Some(position) = cursor.position() else {
+        return;
+    };
+    drag.current = position;
+
+    if mouse.just_pressed(MouseButton::Left) {
+        // Clicks on the UI panels are not meant for the world
+        let over_ui = ui_interactions
+            .iter()
+            .any(|interaction| *interaction != Interaction::None);
+        drag.start = (!over_ui).then_some(position);
+    }
+
+    if !mouse.just_released(MouseButton::Left) || drag.start.is_none() {
+        return;
+    }
+    let edit = match drag.rect() {
+        Some(rect) => EditCommand::Erase(rect),
+        None if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) => {
+            EditCommand::SpawnAnt(position)
+        }
+        None => {
+            // Clicking an ant or food inspects it instead of dropping food on it
+            if targets.select_at(position) {
+                drag.start = None;
+                return;
+            }
+            EditCommand::PlaceFood(position)
+        }
+    };
+    edits.write(edit);
+    drag.start = None;
+}
+
+fn update_selection_box(
+    drag: Res<DragSelection>,
+    mut selection_box: Query<(&mut Sprite, &mut Transform, &mut Visibility), With<SelectionBox>>,
+) {
+    let Ok((mut sprite, mut transform, mut visibility)) = selection_box.single_mut() else {
+        return;
+    };
+    match drag.rect() {
+        Some(rect) => {
+            sprite.custom_size = Some(rect.size());
+            transform.translation = rect.center().extend(Config::SELECTION_Z_LAYER);
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+/// Food lying on the ground, not carried by an ant.
+pub(crate) type LooseFood<'w, 's> =
+    Query<'w, 's, (Entity, &'static Transform), (With<Food>, Without<ChildOf>)>;
+
+/// The entities an `EditCommand::Erase` can hit, and the colonies a spawned
+/// ant can join.
+#[derive(SystemParam)]
+pub struct EditTargets<'w, 's> {
+    all_food: Query<'w, 's, Entity, With<Food>>,
+    loose_food: LooseFood<'w, 's>,
+    ants: Query<'w, 's, (Entity, &'static Transform, Option<&'static Children>), With<Ant>>,
+    colonies: Query<'w, 's, &'static Colony>,
+}
+
+/// Colony whose nest is closest to `position`, or colony 0 without nests.
+fn nearest_colony<'a>(colonies: impl IntoIterator<Item = &'a Colony>, position: Vec2) -> u32 {
+    colonies
+        .into_iter()
+        .min_by(|a, b| {
+            a.nest
+                .distance_squared(position)
+                .total_cmp(&b.nest.distance_squared(position))
+        })
+        .map_or(0, |colony| colony.id)
+}
+
+/// Randomness for hand edits. Kept apart from `SimRng`, which only the fixed
+/// ticks may draw from, so a click doesn't shift a seeded run's sequence.
+pub struct EditRng(StdRng);
+
+impl Default for EditRng {
+    fn default() -> Self {
+        Self(StdRng::from_os_rng())
+    }
+}
+
+pub fn apply_edit_commands(
+    mut edits: MessageReader<EditCommand>,
+    mut commands: Commands,
+    mut spatial_index: ResMut<SpatialIndex>,
+    mut rng: Local<EditRng>,
+    settings: Res<SimulationSettings>,
+    mut backend_events: BackendEventWriter,
+    targets: EditTargets,
+) {
+    // Slot the startup food before any new food takes the next slot
+    backend_events.ensure_food_slots(targets.all_food.iter());
+
+    for edit in edits.read() {
+        match *edit {
+            EditCommand::PlaceFood(position) => {
+                let food = commands
+                    .spawn((
+                        Food,
+                        Sprite {
+                            color: Colors::FOOD,
+                            custom_size: Some(Config::FOOD_SIZE),
+                            ..default()
+                        },
+                        Transform::from_translation(position.extend(Config::FOOD_Z_LAYER)),
+                        Collidable,
+                        Bounding::from_radius(settings.collision_radius()),
+                    ))
+                    .id();
+                spatial_index.update(food, position);
+
+                let food_id = backend_events.spawned_food_id(food);
+                backend_events.write(BackendSimEvent::FoodSpawn {
+                    food_id,
+                    x: position.x,
+                    y: position.y,
+                });
+            }
+            EditCommand::SpawnAnt(position) => {
+                let angle = rng.0.random_range(0.0..std::f32::consts::TAU);
+                let colony_id = nearest_colony(targets.colonies.iter(), position);
+                commands.spawn((
+                    Ant,
+                    ColonyId(colony_id),
+                    Sprite {
+                        color: Colors::colony(colony_id),
+                        custom_size: Some(Config::ANT_SIZE),
+                        ..default()
+                    },
+                    Transform::from_translation(position.extend(Config::ANT_Z_LAYER)),
+                    Velocity(Vec2::from_angle(angle)),
+                    Bounding::from_radius(settings.collision_radius()),
+                    Collidable,
+                    BoundaryWrap,
+                ));
+            }
+            EditCommand::Erase(rect) => {
+                let mut removed_food = Vec::new();
+                for (food, transform) in &targets.loose_food {
+                    if rect.contains(transform.translation.truncate()) {
+                        removed_food.push(food);
+                        commands.entity(food).despawn();
+                    }
+                }
+                for (ant, transform, children) in &targets.ants {
+                    if rect.contains(transform.translation.truncate()) {
+                        // Carried food goes with the ant
+                        if let Some(children) = children {
+                            removed_food.extend(
+                                children
+                                    .iter()
+                                    .filter(|child| targets.all_food.contains(*child)),
+                            );
+                        }
+                        commands.entity(ant).despawn();
+                    }
+                }
+
+                for food in removed_food {
+                    spatial_index.remove(food);
+                    let food_id = backend_events.removed_food_id(food);
+                    backend_events.write(BackendSimEvent::FoodRemove { food_id });
+                }
+            }
+        }
+    }
+}
@@ -1,23 +1,51 @@
+pub mod analytics;
 pub mod boundary;
+pub mod camera;
 pub mod collision;
+pub mod colony;
 pub mod config;
+pub mod experiment;
+pub mod heatmap;
+pub mod inspector;
+pub mod interaction;
 pub mod net;
+pub mod pheromone;
 pub mod runtime;
+pub mod scenario;
+pub mod simulation;
 pub mod spatial_index;
 pub mod ui;
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use derive_more::From;
 use log::{error, warn};
 use rand::Rng;
 
-pub use boundary::{BoundaryPlugin, BoundaryWrap, Bounding};
-pub use collision::{Collidable, CollisionPlugin, HitEvent};
-pub use config::{Colors, Config, SimulationSettings};
+pub use analytics::{
+    AnalyticsPlugin, ClusteringHistory, ClusteringMetric, ClusteringStats, ClusteringSummary,
+    clustering_summary,
+};
+pub use boundary::{BoundaryPlugin, BoundaryWrap, Bounding, WorldBounds};
+pub use camera::{CameraPlugin, WorldCamera, WorldCursor};
+pub use collision::{Collidable, CollisionMode, CollisionPlugin, HitEvent, PreviousPosition};
+pub use colony::{Colony, ColonyId, ColonyPlugin};
+pub use config::{Colors, Config, PickupRule, SimulationSettings};
+pub use heatmap::{HeatmapOverlay, HeatmapPlugin};
+pub use inspector::{Inspected, InspectorPlugin};
+pub use interaction::{EditCommand, InteractionPlugin};
 pub use net::{
-    BackendClientConfig, BackendClientPlugin, BackendSimEvent, PendingBackendEvents,
+    BackendClientConfig, BackendClientPlugin, BackendCommand, BackendDropPolicy,
+    BackendDroppedEvents, BackendEventWriter, BackendShutdownSet, BackendSimEvent,
+    PendingBackendEvents, parse_backend_command,
+};
+pub use pheromone::{PheromoneField, PheromonePlugin};
+pub use runtime::{RuntimeConfig, generate_spawn_layout, spawn_world};
+pub use scenario::Scenario;
+pub use simulation::{
+    AchievedTickRate, RunMode, SimControl, SimReset, SimRng, SimTick, SimulationPlugin,
+    SimulationSet, reset_simulation,
 };
-pub use runtime::{RuntimeConfig, generate_spawn_layout};
 pub use spatial_index::SpatialIndex;
 pub use ui::UiPlugin;
 
@@ -28,8 +56,16 @@ pub struct FrameCount(pub u64);
 pub struct Velocity(pub Vec2);
 
 #[derive(Debug, Component, Default)]
+#[require(GatherCounts)]
 pub struct Ant;
 
+/// Food an ant has picked up and dropped so far.
+#[derive(Debug, Component, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GatherCounts {
+    pub pickups: u32,
+    pub drops: u32,
+}
+
 #[derive(Debug, Component, Default)]
 pub struct Food;
 
@@ -41,101 +77,133 @@ pub struct Cooldown {
 pub fn gatherer_movement(
     time: Res<Time>,
     settings: Res<SimulationSettings>,
-    mut sprite_position: Query<(&Velocity, &mut Transform), With<Ant>>,
+    pheromones: Option<Res<PheromoneField>>,
+    mut sprite_position: Query<(&mut Velocity, &mut Transform, Option<&Children>), With<Ant>>,
 ) {
-    let delta_time = time.delta_secs();
-    let safe_step = settings.safe_step_distance();
-    let speed = settings.effective_speed(delta_time);
-
-    for (velocity, mut transform) in &mut sprite_position {
-        let direction = velocity.0.normalize_or_zero();
-        let desired_movement = direction * speed * delta_time;
-
-        let movement_distance = desired_movement.length();
-        let final_movement = if movement_distance > safe_step {
-            desired_movement.normalize() * safe_step
-        } else {
-            desired_movement
-        };
-
-        transform.translation.x += final_movement.x;
-        transform.translation.y += final_movement.y;
+    // Runs once per fixed tick at base speed: the speed multiplier scales the
+    // number of ticks, so each step stays well below the tunneling limit.
+    let distance = Config::BASE_ANT_SPEED * time.delta_secs();
+    let max_turn = settings.pheromone_steering_rate * time.delta_secs();
+
+    for (mut velocity, mut transform, carrying) in &mut sprite_position {
+        let carrying = carrying.is_some_and(|children| !children.is_empty());
+        if let Some(field) = pheromones.as_deref()
+            && !carrying
+            && max_turn > 0.0
+        {
+            steer_towards_scent(
+                &mut velocity,
+                field,
+                transform.translation.truncate(),
+                max_turn,
+            );
+        }
+
+        let movement = velocity.0.normalize_or_zero() * distance;
+        transform.translation.x += movement.x;
+        transform.translation.y += movement.y;
     }
 }
 
+/// Turns an unladen ant up the pheromone gradient by at most `max_turn` radians.
+fn steer_towards_scent(velocity: &mut Velocity, field: &PheromoneField, pos: Vec2, max_turn: f32) {
+    let gradient = field.gradient_at(pos);
+    if gradient.length() < Config::PHEROMONE_MIN_GRADIENT {
+        return;
+    }
+
+    let angle = velocity.0.angle_to(gradient);
+    let turn = angle.clamp(-max_turn, max_turn);
+    velocity.0 = Vec2::from_angle(turn).rotate(velocity.0);
+}
+
 pub fn ant_hits_system(
     mut ant_hits: MessageReader<HitEvent<Food, Ant>>,
-    mut backend_events: MessageWriter<BackendSimEvent>,
-    mut backend_food_slots: Option<ResMut<net::BackendFoodSlots>>,
+    mut backend_events: BackendEventWriter,
+    mut decisions: PickupDecisions,
     mut commands: Commands,
     mut ant_query: Query<
-        (&mut Velocity, Option<&Children>, &Transform),
+        (
+            &mut Velocity,
+            &mut GatherCounts,
+            Option<&Children>,
+            &Transform,
+            Option<&ColonyId>,
+        ),
         (With<Ant>, Without<Cooldown>),
     >,
     food_entities: Query<Entity, With<Food>>,
     mut food_query: Query<&mut Transform, (With<Food>, Without<Ant>)>,
 ) {
-    let mut rng = rand::rng();
-    if let Some(food_slots) = backend_food_slots.as_mut() {
-        food_slots.ensure_for_entities(food_entities.iter());
-    }
+    backend_events.ensure_food_slots(food_entities.iter());
 
     for hit in ant_hits.read() {
         let ant = hit.hitter();
         let food = hit.hittable();
 
-        if let Ok((mut velocity, carrying, ant_transform)) = ant_query.get_mut(ant) {
+        if let Ok((mut velocity, mut counts, carrying, ant_transform, colony)) =
+            ant_query.get_mut(ant)
+        {
+            let colony_id = colony.map_or(0, |colony| colony.0);
+            let ant_pos = ant_transform.translation.truncate();
+            let laden = carrying.is_some_and(|carrying| !carrying.is_empty());
+            if laden && decisions.settings.return_to_nest {
+                // Carried home instead, see colony::deliver_to_nest_system
+                continue;
+            }
+            let decided = if laden {
+                decisions.should_drop(ant_pos)
+            } else {
+                decisions.should_pick_up(ant_pos, food)
+            };
+            if !decided {
+                // Decide once per encounter rather than re-rolling every tick
+                // while still touching the same item
+                commands.entity(ant).insert(Cooldown {
+                    timer: decisions.settings.pickup_cooldown,
+                });
+                continue;
+            }
+
             if let Some(carrying) = carrying {
                 if !carrying.is_empty() {
                     let carried_food = carrying[0];
+                    put_down_food(
+                        &mut commands,
+                        ant,
+                        carried_food,
+                        ant_transform.translation,
+                        food_query.get_mut(carried_food).ok(),
+                        decisions.settings.pickup_cooldown,
+                    );
+                    counts.drops += 1;
 
-                    // Set food to the ant's world position before detaching,
-                    // because remove::<ChildOf>() keeps the local Transform as-is
-                    // and it would otherwise snap to origin (the old local offset).
-                    if let Ok(mut food_transform) = food_query.get_mut(carried_food) {
-                        food_transform.translation = Vec3::new(
-                            ant_transform.translation.x,
-                            ant_transform.translation.y,
-                            Config::FOOD_Z_LAYER,
-                        );
-                    }
-
-                    commands.entity(carried_food).remove::<ChildOf>();
-                    commands.entity(carried_food).insert(Collidable);
-                    commands.entity(ant).insert(Cooldown {
-                        timer: Config::BASE_PICKUP_COOLDOWN,
-                    });
-
-                    let current_angle = velocity.0.angle_to(Vec2::new(1.0, 0.0));
-                    let angle = current_angle
-                        + std::f32::consts::PI
-                        + rng.random_range(-Config::TURN_ANGLE_RANGE..Config::TURN_ANGLE_RANGE);
-                    let new_direction = Vec2::new(angle.cos(), angle.sin());
-                    *velocity = Velocity(new_direction);
-                    let ant_id = ant.to_bits().to_string();
-                    let food_id = backend_food_slots
-                        .as_ref()
-                        .and_then(|food_slots| food_slots.slot_for(carried_food))
-                        .unwrap_or(carried_food.to_bits() as usize);
+                    let new_direction = turn_around(&mut velocity, decisions.turn_angle());
+                    let ant_id = backend_events.ant_id(ant);
+                    let food_id = backend_events.food_id(carried_food);
                     backend_events.write(BackendSimEvent::FoodDrop {
-                        ant_id: ant_id.clone(),
+                        ant_id,
+                        colony_id,
                         food_id,
                         x: ant_transform.translation.x,
                         y: ant_transform.translation.y,
                         direction_x: new_direction.x,
                         direction_y: new_direction.y,
-                        frame: 0,
+                        frame: backend_events.frame(),
                     });
                     backend_events.write(BackendSimEvent::AntTurnMove {
                         ant_id,
+                        colony_id,
                         x: ant_transform.translation.x,
                         y: ant_transform.translation.y,
                         direction_x: new_direction.x,
                         direction_y: new_direction.y,
-                        frame: 0,
+                        frame: backend_events.frame(),
                     });
                 } else {
-                    warn!("[ant_hits_system] There is Some(carrying) but carrying.is_empty - how come?");
+                    warn!(
+                        "[ant_hits_system] There is Some(carrying) but carrying.is_empty - how come?"
+                    );
                 }
             } else {
                 commands.entity(ant).add_child(food);
@@ -151,40 +219,107 @@ pub fn ant_hits_system(
                 foodpos.translation.x = 0.0;
                 foodpos.translation.y = 0.0;
                 foodpos.translation.z = Config::CARRIED_FOOD_Z_LAYER;
+                counts.pickups += 1;
 
-                let current_angle = velocity.0.angle_to(Vec2::new(1.0, 0.0));
-                let angle = current_angle
-                    + std::f32::consts::PI
-                    + rng.random_range(-Config::TURN_ANGLE_RANGE..Config::TURN_ANGLE_RANGE);
-                let new_direction = Vec2::new(angle.cos(), angle.sin());
-                *velocity = Velocity(new_direction);
-                let ant_id = ant.to_bits().to_string();
-                let food_id = backend_food_slots
-                    .as_ref()
-                    .and_then(|food_slots| food_slots.slot_for(food))
-                    .unwrap_or(food.to_bits() as usize);
+                let new_direction = turn_around(&mut velocity, decisions.turn_angle());
+                let ant_id = backend_events.ant_id(ant);
+                let food_id = backend_events.food_id(food);
                 backend_events.write(BackendSimEvent::FoodPickup {
-                    ant_id: ant_id.clone(),
+                    ant_id,
+                    colony_id,
                     food_id,
                     x: ant_transform.translation.x,
                     y: ant_transform.translation.y,
                     direction_x: new_direction.x,
                     direction_y: new_direction.y,
-                    frame: 0,
+                    frame: backend_events.frame(),
                 });
                 backend_events.write(BackendSimEvent::AntTurnMove {
                     ant_id,
+                    colony_id,
                     x: ant_transform.translation.x,
                     y: ant_transform.translation.y,
                     direction_x: new_direction.x,
                     direction_y: new_direction.y,
-                    frame: 0,
+                    frame: backend_events.frame(),
                 });
             }
         }
     }
 }
 
+/// Detaches carried food at the ant's position and starts the ant's cooldown.
+pub(crate) fn put_down_food(
+    commands: &mut Commands,
+    ant: Entity,
+    carried_food: Entity,
+    at: Vec3,
+    food_transform: Option<Mut<Transform>>,
+    cooldown: f32,
+) {
+    // Set food to the ant's world position before detaching,
+    // because remove::<ChildOf>() keeps the local Transform as-is
+    // and it would otherwise snap to origin (the old local offset).
+    if let Some(mut food_transform) = food_transform {
+        food_transform.translation = Vec3::new(at.x, at.y, Config::FOOD_Z_LAYER);
+    }
+
+    commands.entity(carried_food).remove::<ChildOf>();
+    commands.entity(carried_food).insert(Collidable);
+    commands.entity(ant).insert(Cooldown { timer: cooldown });
+}
+
+/// Reverses the ant's heading, offset by `jitter` radians, and returns the new direction.
+pub(crate) fn turn_around(velocity: &mut Velocity, jitter: f32) -> Vec2 {
+    let current_angle = velocity.0.angle_to(Vec2::new(1.0, 0.0));
+    let angle = current_angle + std::f32::consts::PI + jitter;
+    let new_direction = Vec2::new(angle.cos(), angle.sin());
+    *velocity = Velocity(new_direction);
+    new_direction
+}
+
+/// Random deviation in `(-range, range)` added when an ant turns around.
+/// A zero range turns straight back without drawing.
+pub(crate) fn turn_jitter(rng: &mut SimRng, range: f32) -> f32 {
+    if range > 0.0 {
+        rng.random_range(-range..range)
+    } else {
+        0.0
+    }
+}
+
+/// Randomness and local density used by `ant_hits_system` to decide what an
+/// ant does on contact, following `SimulationSettings::pickup_rule`.
+#[derive(SystemParam)]
+pub struct PickupDecisions<'w> {
+    settings: Res<'w, SimulationSettings>,
+    spatial_index: Res<'w, SpatialIndex>,
+    rng: ResMut<'w, SimRng>,
+}
+
+impl PickupDecisions<'_> {
+    fn should_pick_up(&mut self, pos: Vec2, food: Entity) -> bool {
+        let density = self.spatial_index.density_at(pos, Some(food));
+        let probability = self.settings.pickup_probability(density);
+        self.roll(probability)
+    }
+
+    fn should_drop(&mut self, pos: Vec2) -> bool {
+        let density = self.spatial_index.density_at(pos, None);
+        let probability = self.settings.drop_probability(density);
+        self.roll(probability)
+    }
+
+    pub(crate) fn turn_angle(&mut self) -> f32 {
+        turn_jitter(&mut self.rng, self.settings.turn_angle_range)
+    }
+
+    // Certain outcomes don't draw, so the Always rule keeps its random sequence
+    fn roll(&mut self, probability: f32) -> bool {
+        probability >= 1.0 || self.rng.random::<f32>() < probability
+    }
+}
+
 pub fn cooldown_system(
     time: Res<Time>,
     mut query: Query<(Entity, &mut Cooldown), With<Ant>>,
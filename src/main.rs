@@ -1,112 +1,118 @@
 use an_gatherers::*;
 
+use std::time::Duration;
+
 use bevy::{
+    app::ScheduleRunnerPlugin,
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    log::LogPlugin,
     prelude::*,
-    window::{PresentMode, PrimaryWindow, WindowPosition},
+    window::{PresentMode, WindowPosition},
 };
-use log::{error, info};
+use log::info;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if runtime::help_requested(&args, &["--headless"]) {
+        print!("{}", runtime::USAGE);
+        return;
+    }
+    let runtime = match RuntimeConfig::from_env_and_args(args) {
+        Ok(runtime) => runtime,
+        Err(error) => {
+            eprintln!("error: {error}\n\n{}", runtime::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     info!("Starting gatherers simulation");
-    let runtime = RuntimeConfig::from_env().expect("runtime config should parse");
-    App::new()
-        .insert_resource(ClearColor(Colors::BACKGROUND))
-        .insert_resource(SimulationSettings {
-            speed_multiplier: runtime.startup_speed,
-        })
+    let mut app = App::new();
+    let settings = runtime.simulation_settings();
+    app.insert_resource(ClearColor(Colors::BACKGROUND))
+        .insert_resource(SpatialIndex::new(settings.spatial_cell_size))
+        .insert_resource(settings)
         .insert_resource(BackendClientConfig {
             url: runtime.backend_ws_url.clone(),
             sim_id: runtime.sim_id.clone(),
-        })
-        .insert_resource(runtime)
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            heartbeat_interval_secs: runtime.backend_heartbeat_secs,
+            outbound_capacity: runtime.backend_queue_capacity,
+            outbound_drop_policy: runtime.backend_drop_policy,
+        });
+
+    if runtime.headless {
+        // No window, no rendering: tick at a steady 60 Hz on MinimalPlugins.
+        app.add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(
+            Duration::from_secs_f64(1.0 / 60.0),
+        )))
+        .add_plugins(LogPlugin::default());
+    } else {
+        app.add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 present_mode: PresentMode::AutoVsync,
                 ..Default::default()
             }),
             ..Default::default()
         }))
-        .add_plugins(BoundaryPlugin)
-        .add_plugins(CollisionPlugin::<Food, Ant>::new())
-        .add_plugins(BackendClientPlugin)
+        .add_plugins(CameraPlugin)
         .add_plugins(UiPlugin)
+        .add_plugins(InteractionPlugin)
+        .add_plugins(HeatmapPlugin)
         .add_plugins(FrameTimeDiagnosticsPlugin::default())
         .add_plugins(LogDiagnosticsPlugin::default())
+        .add_systems(Startup, setup_window);
+    }
+
+    app.insert_resource(WorldBounds::new(runtime.resolved_world_size()))
+        .insert_resource(SimRng::from_seed(runtime.seed))
+        .insert_resource(runtime)
+        .add_plugins(SimulationPlugin)
+        .add_plugins(BoundaryPlugin)
+        .add_plugins(PheromonePlugin)
+        .add_plugins(ColonyPlugin)
+        .add_plugins(CollisionPlugin::<Food, Ant>::new())
+        .add_plugins(BackendClientPlugin)
         .add_systems(Startup, setup)
-        .add_systems(Startup, setup_window)
-        .add_systems(Update, gatherer_movement)
-        .add_systems(Update, ant_hits_system)
-        .add_systems(PostUpdate, cooldown_system)
+        .add_systems(PreUpdate, respawn_after_reset.after(reset_simulation))
+        .add_systems(Last, exit_after_tick_budget.before(BackendShutdownSet))
         .run();
 }
 
+fn exit_after_tick_budget(
+    runtime: Res<RuntimeConfig>,
+    tick: Res<SimTick>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if runtime
+        .max_ticks
+        .is_some_and(|max_ticks| tick.0 >= max_ticks)
+    {
+        info!("Ran {} ticks, exiting", tick.0);
+        exit.write(AppExit::Success);
+    }
+}
+
 fn setup(
     mut commands: Commands,
-    primary_window: Query<&Window, With<PrimaryWindow>>,
+    bounds: Res<WorldBounds>,
     runtime: Res<RuntimeConfig>,
     settings: Res<SimulationSettings>,
 ) {
-    let runtime = runtime.as_ref();
-    commands.spawn(Camera2d::default());
-    let window = match primary_window.single() {
-        Ok(window) => window,
-        Err(e) => {
-            error!("Failed to get primary window: {:?}", e);
-            return;
-        }
-    };
-
-    let map_size = Vec2::new(window.width(), window.height());
-    let layout = generate_spawn_layout(map_size, &settings, runtime.seed);
-    let ant_count = spawn_ants(&mut commands, &settings, &layout);
-    let food_count = spawn_food(&mut commands, &settings, &layout);
-
-    info!("Spawned {} ants and {} food items", ant_count, food_count);
+    spawn_world(&mut commands, bounds.size(), &settings, runtime.seed);
 }
 
-fn spawn_ants(
-    commands: &mut Commands,
-    settings: &SimulationSettings,
-    layout: &an_gatherers::runtime::SpawnLayout,
-) -> usize {
-    for ant in &layout.ants {
-        commands.spawn((
-            Ant,
-            Sprite {
-                color: Colors::ANT,
-                custom_size: Some(Config::ANT_SIZE),
-                ..default()
-            },
-            Transform::from_translation(ant.position),
-            Velocity::from(ant.direction),
-            Bounding::from_radius(settings.collision_radius()),
-            Collidable,
-            BoundaryWrap,
-        ));
-    }
-    layout.ants.len()
-}
-
-fn spawn_food(
-    commands: &mut Commands,
-    settings: &SimulationSettings,
-    layout: &an_gatherers::runtime::SpawnLayout,
-) -> usize {
-    for position in &layout.food_positions {
-        commands.spawn((
-            Food,
-            Sprite {
-                color: Colors::FOOD,
-                custom_size: Some(Config::FOOD_SIZE),
-                ..default()
-            },
-            Transform::from_translation(Vec3::new(position.x, position.y, Config::FOOD_Z_LAYER)),
-            Collidable,
-            Bounding::from_radius(settings.collision_radius()),
-        ));
-    }
-    layout.food_positions.len()
+/// Spawns the world again from the seed of a `SimReset`.
+fn respawn_after_reset(
+    mut resets: MessageReader<SimReset>,
+    mut commands: Commands,
+    bounds: Res<WorldBounds>,
+    mut runtime: ResMut<RuntimeConfig>,
+    settings: Res<SimulationSettings>,
+) {
+    let Some(reset) = resets.read().last() else {
+        return;
+    };
+    runtime.seed = Some(reset.seed);
+    spawn_world(&mut commands, bounds.size(), &settings, runtime.seed);
 }
 
 fn setup_window(mut windows: Query<&mut Window>, runtime: Res<RuntimeConfig>) {
@@ -1,11 +1,17 @@
 use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
 
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::ecs::system::SystemParam;
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
 use ewebsock::{Options, WsEvent, WsMessage, WsReceiver, WsSender};
 use log::{error, warn};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{Ant, Food};
+use crate::colony::ColonyId;
+use crate::config::{Config, SimulationSettings};
+use crate::simulation::{SimControl, SimReset, SimTick, reset_simulation};
+use crate::{Ant, Food, boundary::WorldBounds};
 
 pub struct BackendClientPlugin;
 
@@ -13,27 +19,64 @@ impl Plugin for BackendClientPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<BackendClientConfig>()
             .init_resource::<PendingBackendEvents>()
+            .init_resource::<BackendDroppedEvents>()
             .init_resource::<BackendSequence>()
+            .init_resource::<BackendClock>()
             .init_resource::<BackendFoodSlots>()
+            .init_resource::<BackendAntSlots>()
             .add_message::<BackendSimEvent>()
+            .add_message::<BackendCommand>()
+            .add_message::<SimReset>()
+            .add_observer(assign_backend_ant_slot)
             .insert_non_send_resource(BackendConnectionState::default())
+            .add_systems(PreUpdate, apply_backend_commands.before(reset_simulation))
             .add_systems(
                 PostUpdate,
                 (
+                    resync_backend_after_reset,
                     queue_backend_hello_event,
                     queue_backend_food_snapshot_event,
+                    queue_backend_ant_snapshot_event,
+                    queue_backend_heartbeat_event,
                     collect_backend_events,
                     flush_backend_events,
                 )
                     .chain(),
+            )
+            .add_systems(
+                Last,
+                (queue_backend_goodbye_event, flush_backend_events)
+                    .chain()
+                    .in_set(BackendShutdownSet)
+                    .run_if(on_message::<AppExit>),
             );
     }
 }
 
+/// Sends `sim_goodbye` in `Last` once `AppExit` is written. Systems that
+/// write `AppExit` in `Last` should run before it.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BackendShutdownSet;
+
 #[derive(Resource, Clone, Debug)]
 pub struct BackendClientConfig {
     pub url: Option<String>,
     pub sim_id: String,
+    /// Real seconds between `sim_heartbeat` messages
+    pub heartbeat_interval_secs: f32,
+    /// Most messages held in `PendingBackendEvents` while the socket is down
+    pub outbound_capacity: usize,
+    pub outbound_drop_policy: BackendDropPolicy,
+}
+
+/// Which message is dropped when the outbound queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BackendDropPolicy {
+    /// Keep what is queued and drop the new message
+    #[default]
+    DropNewest,
+    /// Drop the oldest queued message to make room
+    DropOldest,
 }
 
 impl Default for BackendClientConfig {
@@ -42,6 +85,9 @@ impl Default for BackendClientConfig {
         Self {
             url,
             sim_id: format!("sim-{}", rand::random::<u64>()),
+            heartbeat_interval_secs: Config::BACKEND_HEARTBEAT_SECS,
+            outbound_capacity: Config::BACKEND_OUTBOUND_CAPACITY,
+            outbound_drop_policy: BackendDropPolicy::default(),
         }
     }
 }
@@ -51,6 +97,9 @@ impl BackendClientConfig {
         Self {
             url: Some(url),
             sim_id,
+            heartbeat_interval_secs: Config::BACKEND_HEARTBEAT_SECS,
+            outbound_capacity: Config::BACKEND_OUTBOUND_CAPACITY,
+            outbound_drop_policy: BackendDropPolicy::default(),
         }
     }
 
@@ -61,57 +110,274 @@ impl BackendClientConfig {
 
 #[derive(Resource, Default, Debug)]
 pub struct PendingBackendEvents {
-    queued: VecDeque<String>,
+    queued: VecDeque<QueuedMessage>,
+}
+
+#[derive(Debug)]
+struct QueuedMessage {
+    json: String,
+    /// Hello, snapshots and goodbye: never dropped, since they open and close
+    /// the session on the backend
+    session: bool,
 }
 
 impl PendingBackendEvents {
     pub fn queued_json_messages(&self) -> Vec<String> {
-        self.queued.iter().cloned().collect()
+        self.queued
+            .iter()
+            .map(|message| message.json.clone())
+            .collect()
     }
 
-    fn push(&mut self, message: String) {
-        self.queued.push_back(message);
+    /// Queues `message` within the configured capacity. Session messages are
+    /// always queued and never evicted. Returns whether a message was dropped.
+    fn push(&mut self, json: String, session: bool, config: &BackendClientConfig) -> bool {
+        if session || self.queued.len() < config.outbound_capacity {
+            self.queued.push_back(QueuedMessage { json, session });
+            return false;
+        }
+
+        if config.outbound_drop_policy == BackendDropPolicy::DropOldest
+            && let Some(oldest) = self.queued.iter().position(|message| !message.session)
+        {
+            self.queued.remove(oldest);
+            self.queued.push_back(QueuedMessage { json, session });
+        }
+        true
     }
 
     fn pop(&mut self) -> Option<String> {
-        self.queued.pop_front()
+        self.queued.pop_front().map(|message| message.json)
+    }
+
+    /// Empties the queue, returning how many non-session messages were lost.
+    /// Session messages are sent again on the next connection.
+    fn clear(&mut self) -> u64 {
+        let lost = self
+            .queued
+            .iter()
+            .filter(|message| !message.session)
+            .count();
+        self.queued.clear();
+        lost as u64
     }
 }
 
-#[derive(Resource, Default, Debug)]
-pub struct BackendFoodSlots {
+/// Outbound messages dropped because `PendingBackendEvents` was full.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendDroppedEvents(pub u64);
+
+/// Stable backend slot ids: dense `0..n` ids handed out in order, never reused.
+#[derive(Default, Debug)]
+pub struct BackendSlots {
     slot_by_entity: HashMap<Entity, usize>,
+    next_slot: usize,
 }
 
-impl BackendFoodSlots {
-    pub(crate) fn ensure_for_entities<I>(&mut self, food_entities: I)
+impl BackendSlots {
+    pub(crate) fn ensure_for_entities<I>(&mut self, entities: I)
     where
         I: IntoIterator<Item = Entity>,
     {
-        let mut entities: Vec<Entity> = food_entities.into_iter().collect();
-        let already_initialized = entities.len() == self.slot_by_entity.len()
-            && entities
-                .iter()
-                .all(|entity| self.slot_by_entity.contains_key(entity));
-        if already_initialized {
+        if self.next_slot > 0 {
             return;
         }
 
+        let mut entities: Vec<Entity> = entities.into_iter().collect();
         entities.sort_by_key(|entity| entity.to_bits());
-        self.slot_by_entity.clear();
-        for (slot, entity) in entities.into_iter().enumerate() {
-            self.slot_by_entity.insert(entity, slot);
+        for entity in entities {
+            self.assign(entity);
         }
     }
 
     pub(crate) fn slot_for(&self, entity: Entity) -> Option<usize> {
         self.slot_by_entity.get(&entity).copied()
     }
+
+    /// The slot of `entity`, assigning the next free one if it has none.
+    pub(crate) fn assign(&mut self, entity: Entity) -> usize {
+        *self.slot_by_entity.entry(entity).or_insert_with(|| {
+            self.next_slot += 1;
+            self.next_slot - 1
+        })
+    }
+
+    pub(crate) fn release(&mut self, entity: Entity) -> Option<usize> {
+        self.slot_by_entity.remove(&entity)
+    }
+}
+
+/// Stable backend ids for food. The startup food gets the dense ids `0..n`;
+/// food placed later gets the next id.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct BackendFoodSlots(BackendSlots);
+
+/// Stable backend ids for ants, assigned in spawn order as each ant is added.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct BackendAntSlots(BackendSlots);
+
+/// Writes simulation events for the backend, naming food and ants by their
+/// stable slot ids when the backend client is running and by entity bits
+/// otherwise.
+#[derive(SystemParam)]
+pub struct BackendEventWriter<'w> {
+    events: MessageWriter<'w, BackendSimEvent>,
+    food_slots: Option<ResMut<'w, BackendFoodSlots>>,
+    ant_slots: Option<Res<'w, BackendAntSlots>>,
+    tick: Option<Res<'w, SimTick>>,
+}
+
+impl BackendEventWriter<'_> {
+    pub fn ensure_food_slots<I>(&mut self, food_entities: I)
+    where
+        I: IntoIterator<Item = Entity>,
+    {
+        if let Some(food_slots) = self.food_slots.as_mut() {
+            food_slots.ensure_for_entities(food_entities);
+        }
+    }
+
+    /// The simulation tick the event happens on.
+    pub fn frame(&self) -> u64 {
+        self.tick.as_ref().map_or(0, |tick| tick.0)
+    }
+
+    pub fn ant_id(&self, ant: Entity) -> usize {
+        self.ant_slots
+            .as_ref()
+            .and_then(|ant_slots| ant_slots.slot_for(ant))
+            .unwrap_or(ant.to_bits() as usize)
+    }
+
+    pub fn food_id(&self, food: Entity) -> usize {
+        self.food_slots
+            .as_ref()
+            .and_then(|food_slots| food_slots.slot_for(food))
+            .unwrap_or(food.to_bits() as usize)
+    }
+
+    /// Id for food placed after startup, taking the next free slot.
+    pub fn spawned_food_id(&mut self, food: Entity) -> usize {
+        self.food_slots
+            .as_mut()
+            .map(|food_slots| food_slots.assign(food))
+            .unwrap_or(food.to_bits() as usize)
+    }
+
+    /// Id of food being deleted, freeing its slot for good.
+    pub fn removed_food_id(&mut self, food: Entity) -> usize {
+        self.food_slots
+            .as_mut()
+            .and_then(|food_slots| food_slots.release(food))
+            .unwrap_or(food.to_bits() as usize)
+    }
+
+    pub fn write(&mut self, event: BackendSimEvent) {
+        self.events.write(event);
+    }
 }
 
 #[derive(Resource, Default)]
 struct BackendSequence(u64);
 
+/// Wall-clock time of the backend session, for `session_started_ms` and the
+/// `timestamp_ms` of every envelope.
+#[derive(Resource, Debug)]
+pub struct BackendClock {
+    session_started_ms: u64,
+    started: Instant,
+}
+
+impl Default for BackendClock {
+    fn default() -> Self {
+        Self {
+            session_started_ms: unix_time_ms(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl BackendClock {
+    pub fn session_started_ms(&self) -> u64 {
+        self.session_started_ms
+    }
+
+    /// Milliseconds since the unix epoch, advancing monotonically from the
+    /// session start.
+    pub fn now_ms(&self) -> u64 {
+        self.session_started_ms + self.started.elapsed().as_millis() as u64
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_time_ms() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_millis() as u64)
+}
+
+// `SystemTime::now` panics in the browser
+#[cfg(target_arch = "wasm32")]
+fn unix_time_ms() -> u64 {
+    #[wasm_bindgen::prelude::wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = Date, js_name = now)]
+        fn date_now() -> f64;
+    }
+
+    date_now() as u64
+}
+
+/// Stamps outgoing envelopes with the sim id, the next sequence number and
+/// the current time.
+#[derive(SystemParam)]
+struct EnvelopeStamp<'w> {
+    config: Res<'w, BackendClientConfig>,
+    sequence: ResMut<'w, BackendSequence>,
+    clock: Res<'w, BackendClock>,
+}
+
+impl EnvelopeStamp<'_> {
+    fn envelope<T: Serialize>(&mut self, event_type: &'static str, payload: T) -> EventEnvelope<T> {
+        EventEnvelope {
+            event_type,
+            sim_id: self.config.sim_id.clone(),
+            seq: next_sequence(&mut self.sequence),
+            timestamp_ms: self.clock.now_ms(),
+            payload,
+        }
+    }
+}
+
+/// Pushes serialized messages into the bounded outbound queue.
+#[derive(SystemParam)]
+struct BackendOutbox<'w> {
+    config: Res<'w, BackendClientConfig>,
+    pending: ResMut<'w, PendingBackendEvents>,
+    dropped: ResMut<'w, BackendDroppedEvents>,
+}
+
+impl BackendOutbox<'_> {
+    fn push(&mut self, message: String) {
+        if self.pending.push(message, false, &self.config) {
+            self.dropped.0 += 1;
+        }
+    }
+
+    /// Queues a hello, snapshot or goodbye, which is kept even when the queue
+    /// is full.
+    fn push_session(&mut self, message: String) {
+        self.pending.push(message, true, &self.config);
+    }
+
+    /// Drops everything queued, counting lost events as dropped.
+    fn clear(&mut self) {
+        self.dropped.0 += self.pending.clear();
+    }
+}
+
 #[derive(Default)]
 struct BackendConnectionState {
     sender: Option<WsSender>,
@@ -119,12 +385,70 @@ struct BackendConnectionState {
     opened: bool,
     hello_queued: bool,
     food_snapshot_queued: bool,
+    ant_snapshot_queued: bool,
+    goodbye_queued: bool,
+    /// Real time before which no reconnect is attempted
+    retry_at: Option<Duration>,
+    backoff: Duration,
+}
+
+impl BackendConnectionState {
+    /// Drops the socket and backs off exponentially before the next attempt.
+    /// If the socket had opened, the backend has seen part of this session,
+    /// so the next one starts over with hello and fresh snapshots.
+    fn disconnect(&mut self, now: Duration, outbox: &mut BackendOutbox) {
+        if self.opened {
+            // Anything still queued predates the new snapshots
+            outbox.clear();
+            self.hello_queued = false;
+            self.food_snapshot_queued = false;
+            self.ant_snapshot_queued = false;
+        }
+
+        self.sender = None;
+        self.receiver = None;
+        self.opened = false;
+        self.backoff = if self.backoff.is_zero() {
+            Duration::from_secs_f32(Config::BACKEND_RECONNECT_MIN_SECS)
+        } else {
+            (self.backoff * 2).min(Duration::from_secs_f32(Config::BACKEND_RECONNECT_MAX_SECS))
+        };
+        self.retry_at = Some(now + self.backoff);
+    }
+}
+
+/// A command the backend sent down the ingest socket, applied in `PreUpdate`.
+#[derive(Message, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum BackendCommand {
+    SetSpeed { speed: f32 },
+    Pause,
+    Resume,
+    Reset { seed: u64 },
+    RequestSnapshot,
+}
+
+#[derive(Deserialize)]
+struct CommandEnvelope {
+    #[serde(rename = "type")]
+    message_type: String,
+    payload: BackendCommand,
+}
+
+/// Decodes a `sim_command` message from the backend.
+pub fn parse_backend_command(text: &str) -> Result<BackendCommand, String> {
+    let envelope: CommandEnvelope = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    if envelope.message_type != "sim_command" {
+        return Err(format!("unexpected message type {}", envelope.message_type));
+    }
+    Ok(envelope.payload)
 }
 
 #[derive(Message, Clone, Debug)]
 pub enum BackendSimEvent {
     FoodPickup {
-        ant_id: String,
+        ant_id: usize,
+        colony_id: u32,
         food_id: usize,
         x: f32,
         y: f32,
@@ -133,7 +457,8 @@ pub enum BackendSimEvent {
         frame: u64,
     },
     FoodDrop {
-        ant_id: String,
+        ant_id: usize,
+        colony_id: u32,
         food_id: usize,
         x: f32,
         y: f32,
@@ -142,13 +467,22 @@ pub enum BackendSimEvent {
         frame: u64,
     },
     AntTurnMove {
-        ant_id: String,
+        ant_id: usize,
+        colony_id: u32,
         x: f32,
         y: f32,
         direction_x: f32,
         direction_y: f32,
         frame: u64,
     },
+    FoodSpawn {
+        food_id: usize,
+        x: f32,
+        y: f32,
+    },
+    FoodRemove {
+        food_id: usize,
+    },
 }
 
 #[derive(Serialize)]
@@ -174,9 +508,24 @@ struct HelloPayload {
 
 #[derive(Serialize)]
 struct FoodSnapshotPayload {
+    /// Every slot handed out so far; the backend marks unlisted ones absent
+    slot_count: usize,
     foods: Vec<StartupFoodPayload>,
 }
 
+#[derive(Serialize)]
+struct AntSnapshotPayload {
+    ants: Vec<StartupAntPayload>,
+}
+
+#[derive(Serialize)]
+struct StartupAntPayload {
+    ant_id: String,
+    colony_id: u32,
+    x: f32,
+    y: f32,
+}
+
 #[derive(Serialize)]
 struct StartupFoodPayload {
     food_id: usize,
@@ -184,9 +533,22 @@ struct StartupFoodPayload {
     y: f32,
 }
 
+#[derive(Serialize)]
+struct HeartbeatPayload {
+    connected_ant_count: usize,
+    known_food_count: usize,
+    dropped_outbound_events: u64,
+}
+
+#[derive(Serialize)]
+struct GoodbyePayload {
+    reason: String,
+}
+
 #[derive(Serialize)]
 struct FoodEventPayload {
     ant_id: String,
+    colony_id: u32,
     food_id: usize,
     x: f32,
     y: f32,
@@ -195,9 +557,22 @@ struct FoodEventPayload {
     frame: u64,
 }
 
+#[derive(Serialize)]
+struct FoodSpawnPayload {
+    food_id: usize,
+    x: f32,
+    y: f32,
+}
+
+#[derive(Serialize)]
+struct FoodRemovePayload {
+    food_id: usize,
+}
+
 #[derive(Serialize)]
 struct TurnMovePayload {
     ant_id: String,
+    colony_id: u32,
     x: f32,
     y: f32,
     direction_x: f32,
@@ -205,59 +580,111 @@ struct TurnMovePayload {
     frame: u64,
 }
 
+fn apply_backend_commands(
+    mut commands: MessageReader<BackendCommand>,
+    mut settings: Option<ResMut<SimulationSettings>>,
+    mut control: Option<ResMut<SimControl>>,
+    mut connection: NonSendMut<BackendConnectionState>,
+) {
+    for command in commands.read() {
+        match *command {
+            BackendCommand::SetSpeed { speed } if !speed.is_finite() => {
+                warn!("Ignoring backend speed {speed}");
+            }
+            BackendCommand::SetSpeed { speed } => {
+                if let Some(settings) = settings.as_mut() {
+                    settings.speed_multiplier = if speed == Config::UNLIMITED_SPEED {
+                        speed
+                    } else {
+                        speed.clamp(Config::MIN_SPEED_MULTIPLIER, Config::MAX_SPEED_MULTIPLIER)
+                    };
+                }
+            }
+            BackendCommand::Pause => {
+                if let Some(control) = control.as_mut() {
+                    control.pause();
+                }
+            }
+            BackendCommand::Resume => {
+                if let Some(control) = control.as_mut() {
+                    control.resume();
+                }
+            }
+            BackendCommand::Reset { seed } => {
+                if let Some(control) = control.as_mut() {
+                    control.request_reset(seed);
+                }
+            }
+            BackendCommand::RequestSnapshot => {
+                connection.food_snapshot_queued = false;
+                connection.ant_snapshot_queued = false;
+            }
+        }
+    }
+}
+
+/// A reset world is a new session for the backend: hello and snapshots again.
+fn resync_backend_after_reset(
+    mut resets: MessageReader<SimReset>,
+    mut connection: NonSendMut<BackendConnectionState>,
+) {
+    if resets.read().count() > 0 {
+        connection.hello_queued = false;
+        connection.food_snapshot_queued = false;
+        connection.ant_snapshot_queued = false;
+    }
+}
+
 fn queue_backend_hello_event(
-    config: Res<BackendClientConfig>,
-    mut pending: ResMut<PendingBackendEvents>,
-    mut sequence: ResMut<BackendSequence>,
+    mut stamp: EnvelopeStamp,
+    mut outbox: BackendOutbox,
     mut connection: NonSendMut<BackendConnectionState>,
     ant_query: Query<Entity, With<Ant>>,
     food_query: Query<Entity, With<Food>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    world_bounds: Option<Res<WorldBounds>>,
 ) {
-    if !config.is_enabled() || connection.hello_queued {
+    if !stamp.config.is_enabled() || connection.hello_queued {
         return;
     }
 
-    let (world_width, world_height) = match window_query.single() {
-        Ok(window) => (window.width(), window.height()),
-        Err(_) => (0.0, 0.0),
+    let (world_width, world_height) = match world_bounds {
+        Some(bounds) => (bounds.size().x, bounds.size().y),
+        None => (0.0, 0.0),
     };
 
-    let envelope = EventEnvelope {
-        event_type: "sim_hello",
-        sim_id: config.sim_id.clone(),
-        seq: next_sequence(&mut sequence),
-        timestamp_ms: 0,
-        payload: HelloPayload {
-            sim_name: config.sim_id.clone(),
+    let envelope = stamp.envelope(
+        "sim_hello",
+        HelloPayload {
+            sim_name: stamp.config.sim_id.clone(),
             source: "rust-bevy",
-            session_started_ms: 0,
+            session_started_ms: stamp.clock.session_started_ms(),
             world_width,
             world_height,
             ant_count: ant_query.iter().count(),
             food_count: food_query.iter().count(),
         },
-    };
+    );
 
     match serde_json::to_string(&envelope) {
         Ok(json) => {
-            pending.push(json);
+            outbox.push_session(json);
             connection.hello_queued = true;
         }
         Err(err) => error!("Failed to serialize sim_hello event: {err}"),
     }
 }
 
+/// Sends the loose food by slot. Carried and deleted food is left out, and
+/// `slot_count` tells the backend those slots are absent.
 fn queue_backend_food_snapshot_event(
-    config: Res<BackendClientConfig>,
-    mut pending: ResMut<PendingBackendEvents>,
-    mut sequence: ResMut<BackendSequence>,
+    mut stamp: EnvelopeStamp,
+    mut outbox: BackendOutbox,
     mut food_slots: ResMut<BackendFoodSlots>,
     mut connection: NonSendMut<BackendConnectionState>,
     all_food_query: Query<Entity, With<Food>>,
     food_query: Query<(Entity, &Transform), (With<Food>, Without<ChildOf>)>,
 ) {
-    if !config.is_enabled() || !connection.hello_queued || connection.food_snapshot_queued {
+    if !stamp.config.is_enabled() || !connection.hello_queued || connection.food_snapshot_queued {
         return;
     }
 
@@ -274,30 +701,153 @@ fn queue_backend_food_snapshot_event(
         })
         .collect();
 
-    let envelope = EventEnvelope {
-        event_type: "sim_food_snapshot",
-        sim_id: config.sim_id.clone(),
-        seq: next_sequence(&mut sequence),
-        timestamp_ms: 0,
-        payload: FoodSnapshotPayload { foods },
-    };
+    let envelope = stamp.envelope(
+        "sim_food_snapshot",
+        FoodSnapshotPayload {
+            slot_count: food_slots.next_slot,
+            foods,
+        },
+    );
 
     match serde_json::to_string(&envelope) {
         Ok(json) => {
-            pending.push(json);
+            outbox.push_session(json);
             connection.food_snapshot_queued = true;
         }
         Err(err) => error!("Failed to serialize sim_food_snapshot event: {err}"),
     }
 }
 
+fn assign_backend_ant_slot(add: On<Add, Ant>, mut ant_slots: ResMut<BackendAntSlots>) {
+    ant_slots.assign(add.entity);
+}
+
+/// Sends the ants once the food snapshot is out, and again whenever ants are
+/// spawned or deleted later, since the backend's `ant_count` follows the
+/// latest snapshot.
+fn queue_backend_ant_snapshot_event(
+    mut stamp: EnvelopeStamp,
+    mut outbox: BackendOutbox,
+    ant_slots: Res<BackendAntSlots>,
+    mut connection: NonSendMut<BackendConnectionState>,
+    ant_query: Query<(Entity, &Transform, Option<&ColonyId>), With<Ant>>,
+    added_ants: Query<(), Added<Ant>>,
+    mut removed_ants: RemovedComponents<Ant>,
+) {
+    if removed_ants.read().count() > 0 || !added_ants.is_empty() {
+        connection.ant_snapshot_queued = false;
+    }
+    if !stamp.config.is_enabled()
+        || !connection.food_snapshot_queued
+        || connection.ant_snapshot_queued
+    {
+        return;
+    }
+
+    let mut slotted: Vec<_> = ant_query
+        .iter()
+        .map(|(entity, transform, colony)| {
+            let slot = ant_slots
+                .slot_for(entity)
+                .expect("ant slot should exist for every ant entity");
+            (slot, transform, colony)
+        })
+        .collect();
+    slotted.sort_by_key(|(slot, _, _)| *slot);
+    let ants = slotted
+        .into_iter()
+        .map(|(slot, transform, colony)| StartupAntPayload {
+            ant_id: slot.to_string(),
+            colony_id: colony.map_or(0, |colony| colony.0),
+            x: transform.translation.x,
+            y: transform.translation.y,
+        })
+        .collect();
+
+    let envelope = stamp.envelope("sim_ant_snapshot", AntSnapshotPayload { ants });
+
+    match serde_json::to_string(&envelope) {
+        Ok(json) => {
+            outbox.push_session(json);
+            connection.ant_snapshot_queued = true;
+        }
+        Err(err) => error!("Failed to serialize sim_ant_snapshot event: {err}"),
+    }
+}
+
+/// Sends a `sim_heartbeat` every `heartbeat_interval_secs` of real time, also
+/// while paused, so the backend can tell an idle sim from a hung one.
+fn queue_backend_heartbeat_event(
+    mut stamp: EnvelopeStamp,
+    mut outbox: BackendOutbox,
+    connection: NonSend<BackendConnectionState>,
+    time: Res<Time<Real>>,
+    mut last_sent: Local<Option<Duration>>,
+    ant_query: Query<(), With<Ant>>,
+    food_query: Query<(), With<Food>>,
+) {
+    if !stamp.config.is_enabled() || !connection.ant_snapshot_queued {
+        return;
+    }
+
+    let now = time.elapsed();
+    let interval = Duration::from_secs_f32(stamp.config.heartbeat_interval_secs);
+    // The first heartbeat is due one interval after the startup snapshots
+    let last = *last_sent.get_or_insert(now);
+    if now - last < interval {
+        return;
+    }
+    *last_sent = Some(now);
+
+    let envelope = stamp.envelope(
+        "sim_heartbeat",
+        HeartbeatPayload {
+            connected_ant_count: ant_query.iter().count(),
+            known_food_count: food_query.iter().count(),
+            dropped_outbound_events: outbox.dropped.0,
+        },
+    );
+
+    match serde_json::to_string(&envelope) {
+        Ok(json) => outbox.push(json),
+        Err(err) => error!("Failed to serialize sim_heartbeat event: {err}"),
+    }
+}
+
+/// Queues `sim_goodbye` when the app exits, so the backend drops the sim right
+/// away instead of waiting for the socket to close.
+fn queue_backend_goodbye_event(
+    mut stamp: EnvelopeStamp,
+    mut outbox: BackendOutbox,
+    mut connection: NonSendMut<BackendConnectionState>,
+    mut exits: MessageReader<AppExit>,
+) {
+    let Some(exit) = exits.read().last() else {
+        return;
+    };
+    if !stamp.config.is_enabled() || !connection.hello_queued || connection.goodbye_queued {
+        return;
+    }
+
+    let reason = match exit {
+        AppExit::Success => "shutdown".to_string(),
+        AppExit::Error(code) => format!("exit code {code}"),
+    };
+    match serde_json::to_string(&stamp.envelope("sim_goodbye", GoodbyePayload { reason })) {
+        Ok(json) => {
+            outbox.push_session(json);
+            connection.goodbye_queued = true;
+        }
+        Err(err) => error!("Failed to serialize sim_goodbye event: {err}"),
+    }
+}
+
 fn collect_backend_events(
-    config: Res<BackendClientConfig>,
-    mut pending: ResMut<PendingBackendEvents>,
-    mut sequence: ResMut<BackendSequence>,
+    mut stamp: EnvelopeStamp,
+    mut outbox: BackendOutbox,
     mut events: MessageReader<BackendSimEvent>,
 ) {
-    if !config.is_enabled() {
+    if !stamp.config.is_enabled() {
         return;
     }
 
@@ -305,19 +855,18 @@ fn collect_backend_events(
         let serialized = match event {
             BackendSimEvent::FoodPickup {
                 ant_id,
+                colony_id,
                 food_id,
                 x,
                 y,
                 direction_x,
                 direction_y,
                 frame,
-            } => serde_json::to_string(&EventEnvelope {
-                event_type: "food_pickup",
-                sim_id: config.sim_id.clone(),
-                seq: next_sequence(&mut sequence),
-                timestamp_ms: 0,
-                payload: FoodEventPayload {
-                    ant_id: ant_id.clone(),
+            } => serde_json::to_string(&stamp.envelope(
+                "food_pickup",
+                FoodEventPayload {
+                    ant_id: ant_id.to_string(),
+                    colony_id: *colony_id,
                     food_id: food_id.clone(),
                     x: *x,
                     y: *y,
@@ -325,22 +874,21 @@ fn collect_backend_events(
                     direction_y: *direction_y,
                     frame: *frame,
                 },
-            }),
+            )),
             BackendSimEvent::FoodDrop {
                 ant_id,
+                colony_id,
                 food_id,
                 x,
                 y,
                 direction_x,
                 direction_y,
                 frame,
-            } => serde_json::to_string(&EventEnvelope {
-                event_type: "food_drop",
-                sim_id: config.sim_id.clone(),
-                seq: next_sequence(&mut sequence),
-                timestamp_ms: 0,
-                payload: FoodEventPayload {
-                    ant_id: ant_id.clone(),
+            } => serde_json::to_string(&stamp.envelope(
+                "food_drop",
+                FoodEventPayload {
+                    ant_id: ant_id.to_string(),
+                    colony_id: *colony_id,
                     food_id: food_id.clone(),
                     x: *x,
                     y: *y,
@@ -348,50 +896,65 @@ fn collect_backend_events(
                     direction_y: *direction_y,
                     frame: *frame,
                 },
-            }),
+            )),
             BackendSimEvent::AntTurnMove {
                 ant_id,
+                colony_id,
                 x,
                 y,
                 direction_x,
                 direction_y,
                 frame,
-            } => serde_json::to_string(&EventEnvelope {
-                event_type: "ant_turn_move",
-                sim_id: config.sim_id.clone(),
-                seq: next_sequence(&mut sequence),
-                timestamp_ms: 0,
-                payload: TurnMovePayload {
-                    ant_id: ant_id.clone(),
+            } => serde_json::to_string(&stamp.envelope(
+                "ant_turn_move",
+                TurnMovePayload {
+                    ant_id: ant_id.to_string(),
+                    colony_id: *colony_id,
                     x: *x,
                     y: *y,
                     direction_x: *direction_x,
                     direction_y: *direction_y,
                     frame: *frame,
                 },
-            }),
+            )),
+            BackendSimEvent::FoodSpawn { food_id, x, y } => serde_json::to_string(&stamp.envelope(
+                "food_spawn",
+                FoodSpawnPayload {
+                    food_id: *food_id,
+                    x: *x,
+                    y: *y,
+                },
+            )),
+            BackendSimEvent::FoodRemove { food_id } => serde_json::to_string(
+                &stamp.envelope("food_remove", FoodRemovePayload { food_id: *food_id }),
+            ),
         };
 
         match serialized {
-            Ok(json) => pending.push(json),
+            Ok(json) => outbox.push(json),
             Err(err) => error!("Failed to serialize backend event: {err}"),
         }
     }
 }
 
 fn flush_backend_events(
-    config: Res<BackendClientConfig>,
-    mut pending: ResMut<PendingBackendEvents>,
+    mut outbox: BackendOutbox,
     mut connection: NonSendMut<BackendConnectionState>,
+    time: Res<Time<Real>>,
+    mut commands: MessageWriter<BackendCommand>,
 ) {
-    if !config.is_enabled() {
+    if !outbox.config.is_enabled() {
         return;
     }
 
+    let now = time.elapsed();
     if connection.sender.is_none() {
-        let Some(url) = &config.url else {
+        let Some(url) = &outbox.config.url else {
             return;
         };
+        if connection.retry_at.is_some_and(|retry_at| now < retry_at) {
+            return;
+        }
 
         match ewebsock::connect(url.clone(), Options::default()) {
             Ok((sender, receiver)) => {
@@ -401,6 +964,7 @@ fn flush_backend_events(
             }
             Err(err) => {
                 warn!("Failed to connect backend websocket client: {err}");
+                connection.disconnect(now, &mut outbox);
                 return;
             }
         }
@@ -412,14 +976,26 @@ fn flush_backend_events(
         };
 
         match event {
-            WsEvent::Opened => connection.opened = true,
+            WsEvent::Opened => {
+                connection.opened = true;
+                connection.backoff = Duration::ZERO;
+            }
             WsEvent::Closed => {
-                connection.sender = None;
-                connection.receiver = None;
-                connection.opened = false;
+                connection.disconnect(now, &mut outbox);
+                return;
+            }
+            // ewebsock ends the connection after reporting an error
+            WsEvent::Error(err) => {
+                warn!("Backend websocket error: {err}");
+                connection.disconnect(now, &mut outbox);
                 return;
             }
-            WsEvent::Error(err) => warn!("Backend websocket error: {err}"),
+            WsEvent::Message(WsMessage::Text(text)) => match parse_backend_command(&text) {
+                Ok(command) => {
+                    commands.write(command);
+                }
+                Err(err) => warn!("Ignoring backend message {text}: {err}"),
+            },
             WsEvent::Message(_) => {}
         }
     }
@@ -429,7 +1005,7 @@ fn flush_backend_events(
     }
 
     if let Some(sender) = &mut connection.sender {
-        while let Some(message) = pending.pop() {
+        while let Some(message) = outbox.pending.pop() {
             sender.send(WsMessage::Text(message));
         }
     }
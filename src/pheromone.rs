@@ -0,0 +1,190 @@
+//! Grid-based pheromone field for stigmergic foraging.
+//! Ants carrying food lay scent; each tick the scent evaporates and diffuses
+//! into neighbouring cells, and unladen ants steer up the gradient in
+//! `gatherer_movement`. A deposit rate of 0 leaves the pure random walk.
+
+use bevy::prelude::*;
+
+use crate::Ant;
+use crate::boundary::WorldBounds;
+use crate::config::{Config, SimulationSettings};
+use crate::simulation::{SimReset, SimulationSet, reset_simulation};
+
+pub struct PheromonePlugin;
+
+impl Plugin for PheromonePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PheromoneField>()
+            .add_message::<SimReset>()
+            .add_systems(
+                FixedUpdate,
+                (deposit_pheromone_system, update_pheromone_field_system)
+                    .chain()
+                    .in_set(SimulationSet::Pheromone),
+            )
+            .add_systems(
+                PreUpdate,
+                clear_pheromone_field_on_reset.after(reset_simulation),
+            );
+    }
+}
+
+/// Scent concentration per grid cell, covering the world bounds.
+/// Cells wrap at the edges like the ants do.
+#[derive(Resource, Debug, Clone)]
+pub struct PheromoneField {
+    width: usize,
+    height: usize,
+    origin: Vec2,
+    cells: Vec<f32>,
+    scratch: Vec<f32>,
+}
+
+impl FromWorld for PheromoneField {
+    fn from_world(world: &mut World) -> Self {
+        let bounds = world
+            .get_resource::<WorldBounds>()
+            .copied()
+            .unwrap_or_default();
+        Self::new(&bounds)
+    }
+}
+
+impl PheromoneField {
+    pub fn new(bounds: &WorldBounds) -> Self {
+        let width = (bounds.size().x / Config::PHEROMONE_CELL_SIZE)
+            .ceil()
+            .max(1.0) as usize;
+        let height = (bounds.size().y / Config::PHEROMONE_CELL_SIZE)
+            .ceil()
+            .max(1.0) as usize;
+        Self {
+            width,
+            height,
+            origin: -bounds.half_size(),
+            cells: vec![0.0; width * height],
+            scratch: vec![0.0; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Scent in the cell containing `pos`.
+    pub fn concentration_at(&self, pos: Vec2) -> f32 {
+        let (x, y) = self.cell_of(pos);
+        self.cells[self.index(x, y)]
+    }
+
+    pub fn total(&self) -> f32 {
+        self.cells.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|&cell| cell == 0.0)
+    }
+
+    pub fn deposit(&mut self, pos: Vec2, amount: f32) {
+        let (x, y) = self.cell_of(pos);
+        let index = self.index(x, y);
+        self.cells[index] += amount;
+    }
+
+    /// Direction of increasing scent at `pos`, from central differences
+    /// between the neighbouring cells.
+    pub fn gradient_at(&self, pos: Vec2) -> Vec2 {
+        let (x, y) = self.cell_of(pos);
+        let (x, y) = (x as isize, y as isize);
+        let dx = self.wrapped(x + 1, y) - self.wrapped(x - 1, y);
+        let dy = self.wrapped(x, y + 1) - self.wrapped(x, y - 1);
+        Vec2::new(dx, dy) / (2.0 * Config::PHEROMONE_CELL_SIZE)
+    }
+
+    /// Advances the field by `delta_secs`: evaporation, then diffusion.
+    pub fn step(&mut self, evaporation_rate: f32, diffusion_rate: f32, delta_secs: f32) {
+        let retained = (-evaporation_rate * delta_secs).exp();
+        let diffusion = (diffusion_rate * delta_secs).clamp(0.0, 1.0);
+
+        for y in 0..self.height as isize {
+            for x in 0..self.width as isize {
+                let here = self.wrapped(x, y);
+                let neighbours = self.wrapped(x + 1, y)
+                    + self.wrapped(x - 1, y)
+                    + self.wrapped(x, y + 1)
+                    + self.wrapped(x, y - 1);
+                let index = self.index(x as usize, y as usize);
+                self.scratch[index] = retained * (here + diffusion * (neighbours / 4.0 - here));
+            }
+        }
+        std::mem::swap(&mut self.cells, &mut self.scratch);
+    }
+
+    fn cell_of(&self, pos: Vec2) -> (usize, usize) {
+        let local = (pos - self.origin) / Config::PHEROMONE_CELL_SIZE;
+        (
+            (local.x.floor() as isize).rem_euclid(self.width as isize) as usize,
+            (local.y.floor() as isize).rem_euclid(self.height as isize) as usize,
+        )
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    fn wrapped(&self, x: isize, y: isize) -> f32 {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        self.cells[self.index(x, y)]
+    }
+}
+
+/// The old world's scent trails don't belong to the new one.
+fn clear_pheromone_field_on_reset(
+    mut resets: MessageReader<SimReset>,
+    bounds: Option<Res<WorldBounds>>,
+    mut field: ResMut<PheromoneField>,
+) {
+    if resets.read().count() > 0 {
+        *field = PheromoneField::new(&bounds.as_deref().copied().unwrap_or_default());
+    }
+}
+
+fn deposit_pheromone_system(
+    time: Res<Time>,
+    settings: Res<SimulationSettings>,
+    mut field: ResMut<PheromoneField>,
+    ants: Query<(&Transform, Option<&Children>), With<Ant>>,
+) {
+    let amount = settings.pheromone_deposit_rate * time.delta_secs();
+    if amount <= 0.0 {
+        return;
+    }
+
+    for (transform, carrying) in ants.iter() {
+        if carrying.is_some_and(|children| !children.is_empty()) {
+            field.deposit(transform.translation.truncate(), amount);
+        }
+    }
+}
+
+fn update_pheromone_field_system(
+    time: Res<Time>,
+    settings: Res<SimulationSettings>,
+    mut field: ResMut<PheromoneField>,
+) {
+    // Nothing to evaporate or diffuse, and nothing will be laid
+    if settings.pheromone_deposit_rate <= 0.0 && field.is_empty() {
+        return;
+    }
+
+    field.step(
+        settings.pheromone_evaporation_rate,
+        settings.pheromone_diffusion_rate,
+        time.delta_secs(),
+    );
+}
@@ -3,17 +3,73 @@ use std::collections::HashMap;
 use bevy::prelude::*;
 use rand::{Rng, SeedableRng, rngs::StdRng};
 
-use crate::{Config, SimulationSettings};
+use crate::boundary::{BoundaryWrap, Bounding};
+use crate::collision::Collidable;
+use crate::colony::{Colony, ColonyId};
+use crate::config::Colors;
+use crate::net::BackendDropPolicy;
+use crate::scenario::Scenario;
+use crate::{Ant, Config, Food, SimulationSettings, Velocity};
+
+/// Command-line help for the an-gatherers binary.
+pub const USAGE: &str = "\
+Usage: an-gatherers [OPTIONS]
+
+Options override the matching GATHERERS_* environment variables.
+
+      --seed <N>             Seed for the spawn layout and simulation (GATHERERS_SIM_SEED)
+      --speed <X>            Startup speed multiplier (GATHERERS_STARTUP_SPEED)
+      --backend-url <URL>    Backend ingest websocket URL (GATHERERS_BACKEND_WS_URL)
+      --sim-id <ID>          Simulation id reported to the backend (GATHERERS_SIM_ID)
+      --window-size <WxH>    Window size, e.g. 800x600 (GATHERERS_WINDOW_WIDTH/HEIGHT)
+      --headless             Run without a window (GATHERERS_HEADLESS)
+      --ticks <N>            Exit after N simulation ticks (GATHERERS_TICKS)
+      --scenario <PATH>      Scenario file, TOML or JSON (GATHERERS_SCENARIO)
+  -h, --help                 Print this help
+";
+
+/// Whether the command line asks for `USAGE` instead of a run. Flags other
+/// than `switches` take the next argument as their value, so
+/// `--scenario --help` names a file rather than asking for help.
+pub fn help_requested<I, S>(args: I, switches: &[&str]) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        if matches!(arg, "--help" | "-h") {
+            return true;
+        }
+        if arg.starts_with("--") && !arg.contains('=') && !switches.contains(&arg) {
+            args.next();
+        }
+    }
+    false
+}
 
 #[derive(Resource, Clone, Debug, PartialEq)]
 pub struct RuntimeConfig {
     pub backend_ws_url: Option<String>,
     pub sim_id: String,
+    /// Real seconds between backend heartbeats
+    pub backend_heartbeat_secs: f32,
+    /// Outbound backend queue size and what to drop when it is full
+    pub backend_queue_capacity: usize,
+    pub backend_drop_policy: BackendDropPolicy,
     pub seed: Option<u64>,
     pub startup_speed: f32,
     pub window_title: String,
     pub window_position: Option<(i32, i32)>,
     pub window_size: Option<(u32, u32)>,
+    pub world_size: Option<(u32, u32)>,
+    pub headless: bool,
+    pub colony_count: u32,
+    pub return_to_nest: bool,
+    pub scenario: Scenario,
+    /// Exit once this many simulation ticks have run
+    pub max_ticks: Option<u64>,
 }
 
 impl Default for RuntimeConfig {
@@ -21,11 +77,20 @@ impl Default for RuntimeConfig {
         Self {
             backend_ws_url: std::env::var("GATHERERS_BACKEND_WS_URL").ok(),
             sim_id: format!("sim-{}", rand::random::<u64>()),
+            backend_heartbeat_secs: Config::BACKEND_HEARTBEAT_SECS,
+            backend_queue_capacity: Config::BACKEND_OUTBOUND_CAPACITY,
+            backend_drop_policy: BackendDropPolicy::default(),
             seed: None,
             startup_speed: SimulationSettings::default().speed_multiplier,
             window_title: "an-gatherers".to_string(),
             window_position: None,
             window_size: None,
+            world_size: None,
+            headless: false,
+            colony_count: SimulationSettings::default().colony_count,
+            return_to_nest: SimulationSettings::default().return_to_nest,
+            scenario: Scenario::default(),
+            max_ticks: None,
         }
     }
 }
@@ -53,6 +118,30 @@ impl RuntimeConfig {
         if let Some(sim_id) = values.get("GATHERERS_SIM_ID") {
             config.sim_id = sim_id.clone();
         }
+        if let Some(secs) = values.get("GATHERERS_BACKEND_HEARTBEAT_SECS") {
+            config.backend_heartbeat_secs = parse_f32("GATHERERS_BACKEND_HEARTBEAT_SECS", secs)?;
+            if config.backend_heartbeat_secs <= 0.0 {
+                return Err("GATHERERS_BACKEND_HEARTBEAT_SECS must be positive".to_string());
+            }
+        }
+        if let Some(capacity) = values.get("GATHERERS_BACKEND_QUEUE_CAPACITY") {
+            config.backend_queue_capacity =
+                parse_u64("GATHERERS_BACKEND_QUEUE_CAPACITY", capacity)? as usize;
+            if config.backend_queue_capacity == 0 {
+                return Err("GATHERERS_BACKEND_QUEUE_CAPACITY must be at least 1".to_string());
+            }
+        }
+        if let Some(policy) = values.get("GATHERERS_BACKEND_DROP_POLICY") {
+            config.backend_drop_policy = match policy.trim().to_ascii_lowercase().as_str() {
+                "newest" => BackendDropPolicy::DropNewest,
+                "oldest" => BackendDropPolicy::DropOldest,
+                _ => {
+                    return Err(format!(
+                        "GATHERERS_BACKEND_DROP_POLICY must be newest or oldest, got {policy:?}"
+                    ));
+                }
+            };
+        }
         if let Some(seed) = values.get("GATHERERS_SIM_SEED") {
             config.seed = Some(parse_u64("GATHERERS_SIM_SEED", seed)?);
         }
@@ -62,6 +151,24 @@ impl RuntimeConfig {
         if let Some(title) = values.get("GATHERERS_WINDOW_TITLE") {
             config.window_title = title.clone();
         }
+        if let Some(headless) = values.get("GATHERERS_HEADLESS") {
+            config.headless = parse_bool("GATHERERS_HEADLESS", headless)?;
+        }
+        if let Some(colonies) = values.get("GATHERERS_COLONIES") {
+            config.colony_count = parse_u32("GATHERERS_COLONIES", colonies)?;
+            if config.colony_count == 0 {
+                return Err("GATHERERS_COLONIES must be at least 1".to_string());
+            }
+        }
+        if let Some(return_to_nest) = values.get("GATHERERS_RETURN_TO_NEST") {
+            config.return_to_nest = parse_bool("GATHERERS_RETURN_TO_NEST", return_to_nest)?;
+        }
+        if let Some(path) = values.get("GATHERERS_SCENARIO") {
+            config.scenario = Scenario::load(path)?;
+        }
+        if let Some(ticks) = values.get("GATHERERS_TICKS") {
+            config.max_ticks = Some(parse_u64("GATHERERS_TICKS", ticks)?);
+        }
 
         let window_x = values
             .get("GATHERERS_WINDOW_X")
@@ -84,11 +191,11 @@ impl RuntimeConfig {
 
         let window_width = values
             .get("GATHERERS_WINDOW_WIDTH")
-            .map(|value| parse_u32("GATHERERS_WINDOW_WIDTH", value))
+            .map(|value| parse_extent("GATHERERS_WINDOW_WIDTH", value))
             .transpose()?;
         let window_height = values
             .get("GATHERERS_WINDOW_HEIGHT")
-            .map(|value| parse_u32("GATHERERS_WINDOW_HEIGHT", value))
+            .map(|value| parse_extent("GATHERERS_WINDOW_HEIGHT", value))
             .transpose()?;
         config.window_size = match (window_width, window_height) {
             (Some(width), Some(height)) => Some((width, height)),
@@ -101,44 +208,173 @@ impl RuntimeConfig {
             }
         };
 
+        let world_width = values
+            .get("GATHERERS_WORLD_WIDTH")
+            .map(|value| parse_extent("GATHERERS_WORLD_WIDTH", value))
+            .transpose()?;
+        let world_height = values
+            .get("GATHERERS_WORLD_HEIGHT")
+            .map(|value| parse_extent("GATHERERS_WORLD_HEIGHT", value))
+            .transpose()?;
+        config.world_size = match (world_width, world_height) {
+            (Some(width), Some(height)) => Some((width, height)),
+            (None, None) => None,
+            _ => {
+                return Err(
+                    "GATHERERS_WORLD_WIDTH and GATHERERS_WORLD_HEIGHT must be provided together"
+                        .to_string(),
+                );
+            }
+        };
+
         Ok(config)
     }
+
+    /// Environment first, then the command-line flags on top.
+    pub fn from_env_and_args<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut config = Self::from_env()?;
+        config.apply_args(args)?;
+        Ok(config)
+    }
+
+    /// Overrides the config with command-line flags (see `USAGE`). Flag values
+    /// go through the same parsers as the env vars. `--help` is ignored here;
+    /// check `help_requested` first.
+    pub fn apply_args<I, S>(&mut self, args: I) -> Result<(), String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{flag} requires a value"))
+            };
+
+            match flag.as_str() {
+                "--seed" => self.seed = Some(parse_u64("--seed", &value()?)?),
+                "--speed" => self.startup_speed = parse_f32("--speed", &value()?)?,
+                "--backend-url" => self.backend_ws_url = Some(value()?),
+                "--sim-id" => self.sim_id = value()?,
+                "--window-size" => {
+                    self.window_size = Some(parse_size("--window-size", &value()?)?);
+                }
+                "--headless" => {
+                    self.headless = match &inline_value {
+                        Some(value) => parse_bool("--headless", value)?,
+                        None => true,
+                    }
+                }
+                "--ticks" => self.max_ticks = Some(parse_u64("--ticks", &value()?)?),
+                "--scenario" => self.scenario = Scenario::load(value()?)?,
+                "--help" | "-h" => {}
+                _ => return Err(format!("unknown argument {flag:?}")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Initial simulation settings: the defaults with the configured speed,
+    /// colonies and scenario overrides applied.
+    pub fn simulation_settings(&self) -> SimulationSettings {
+        let mut settings = SimulationSettings {
+            speed_multiplier: self.startup_speed,
+            colony_count: self.colony_count,
+            return_to_nest: self.return_to_nest,
+            ..Default::default()
+        };
+        self.scenario.apply(&mut settings);
+        settings
+    }
+
+    /// Size of the simulated world: the explicit world size if configured,
+    /// else the configured window size, else `Config::DEFAULT_WORLD_SIZE`.
+    /// Never follows the live window, so resizing does not change the world.
+    pub fn resolved_world_size(&self) -> Vec2 {
+        match self.world_size.or(self.window_size) {
+            Some((width, height)) => Vec2::new(width as f32, height as f32),
+            None => Config::DEFAULT_WORLD_SIZE,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SpawnLayout {
+    pub nests: Vec<NestSpawn>,
     pub ants: Vec<AntSpawn>,
     pub food_positions: Vec<Vec2>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct NestSpawn {
+    pub colony_id: u32,
+    pub position: Vec2,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct AntSpawn {
+    pub colony_id: u32,
     pub position: Vec3,
     pub direction: Vec2,
 }
 
+/// One nest per colony, evenly spaced on an ellipse around the world centre
+/// (a single colony nests at the centre). Ants are dealt to the colonies in
+/// turn and scattered within `Config::NEST_SPAWN_RADIUS` of their nest.
 pub fn generate_spawn_layout(
     map_size: Vec2,
-    _settings: &SimulationSettings,
+    settings: &SimulationSettings,
     seed: Option<u64>,
 ) -> SpawnLayout {
     let half_x = (map_size.x / 2.0) as i32;
     let half_y = (map_size.y / 2.0) as i32;
     let mut rng = build_rng(seed);
 
-    let ants = (-half_x..half_x)
-        .step_by(Config::ANT_SPAWN_STEP as usize)
-        .map(|x| {
+    let colony_count = settings.colony_count.max(1);
+    let nests: Vec<NestSpawn> = (0..colony_count)
+        .map(|colony_id| {
+            let position = if colony_count == 1 {
+                Vec2::ZERO
+            } else {
+                let angle = colony_id as f32 / colony_count as f32 * std::f32::consts::TAU;
+                Vec2::new(angle.cos(), angle.sin()) * map_size / 4.0
+            };
+            NestSpawn {
+                colony_id,
+                position,
+            }
+        })
+        .collect();
+
+    let ant_count = (map_size.x / settings.ant_spawn_step).ceil() as usize;
+    let ants = (0..ant_count)
+        .map(|index| {
+            let nest = &nests[index % nests.len()];
+            let offset_angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let offset_distance = Config::NEST_SPAWN_RADIUS * rng.random::<f32>().sqrt();
+            let offset = Vec2::new(offset_angle.cos(), offset_angle.sin()) * offset_distance;
             let angle = rng.random_range(0.0..2.0 * std::f32::consts::PI);
             let direction = Vec2::new(angle.cos(), angle.sin());
             AntSpawn {
-                position: Vec3::new(x as f32, Config::ANT_SPAWN_Y, Config::ANT_Z_LAYER),
+                colony_id: nest.colony_id,
+                position: (nest.position + offset).extend(Config::ANT_Z_LAYER),
                 direction,
             }
         })
         .collect();
 
-    let food_positions = (0..Config::FOOD_COUNT)
+    let food_positions = (0..settings.food_count)
         .map(|_| {
             let x = rng.random_range(-half_x..half_x);
             let y = rng.random_range(-half_y..half_y);
@@ -147,11 +383,97 @@ pub fn generate_spawn_layout(
         .collect();
 
     SpawnLayout {
+        nests,
         ants,
         food_positions,
     }
 }
 
+/// Spawns the nests, ants and food of the seeded layout: the world the binary
+/// starts with and the one every batch run starts from.
+pub fn spawn_world(
+    commands: &mut Commands,
+    map_size: Vec2,
+    settings: &SimulationSettings,
+    seed: Option<u64>,
+) {
+    let layout = generate_spawn_layout(map_size, settings, seed);
+    spawn_nests(commands, &layout);
+    let ant_count = spawn_ants(commands, settings, &layout);
+    let food_count = spawn_food(commands, settings, &layout);
+
+    info!(
+        "Spawned {} ants in {} colonies and {} food items",
+        ant_count,
+        layout.nests.len(),
+        food_count
+    );
+}
+
+fn spawn_nests(commands: &mut Commands, layout: &SpawnLayout) {
+    for nest in &layout.nests {
+        let color = Colors::colony(nest.colony_id);
+        commands.spawn((
+            Colony {
+                id: nest.colony_id,
+                nest: nest.position,
+                color,
+            },
+            Sprite {
+                color: color.with_alpha(0.4),
+                custom_size: Some(Config::NEST_SIZE),
+                ..default()
+            },
+            Transform::from_translation(nest.position.extend(Config::NEST_Z_LAYER)),
+        ));
+    }
+}
+
+fn spawn_ants(
+    commands: &mut Commands,
+    settings: &SimulationSettings,
+    layout: &SpawnLayout,
+) -> usize {
+    for ant in &layout.ants {
+        commands.spawn((
+            Ant,
+            ColonyId(ant.colony_id),
+            Sprite {
+                color: Colors::colony(ant.colony_id),
+                custom_size: Some(Config::ANT_SIZE),
+                ..default()
+            },
+            Transform::from_translation(ant.position),
+            Velocity::from(ant.direction),
+            Bounding::from_radius(settings.collision_radius()),
+            Collidable,
+            BoundaryWrap,
+        ));
+    }
+    layout.ants.len()
+}
+
+fn spawn_food(
+    commands: &mut Commands,
+    settings: &SimulationSettings,
+    layout: &SpawnLayout,
+) -> usize {
+    for position in &layout.food_positions {
+        commands.spawn((
+            Food,
+            Sprite {
+                color: Colors::FOOD,
+                custom_size: Some(Config::FOOD_SIZE),
+                ..default()
+            },
+            Transform::from_translation(Vec3::new(position.x, position.y, Config::FOOD_Z_LAYER)),
+            Collidable,
+            Bounding::from_radius(settings.collision_radius()),
+        ));
+    }
+    layout.food_positions.len()
+}
+
 fn build_rng(seed: Option<u64>) -> StdRng {
     match seed {
         Some(seed) => StdRng::seed_from_u64(seed),
@@ -159,26 +481,53 @@ fn build_rng(seed: Option<u64>) -> StdRng {
     }
 }
 
-fn parse_u64(name: &str, value: &str) -> Result<u64, String> {
+pub(crate) fn parse_u64(name: &str, value: &str) -> Result<u64, String> {
     value
         .parse()
         .map_err(|_| format!("{name} must be a valid u64, got {value:?}"))
 }
 
-fn parse_u32(name: &str, value: &str) -> Result<u32, String> {
+pub(crate) fn parse_u32(name: &str, value: &str) -> Result<u32, String> {
     value
         .parse()
         .map_err(|_| format!("{name} must be a valid u32, got {value:?}"))
 }
 
-fn parse_i32(name: &str, value: &str) -> Result<i32, String> {
+pub(crate) fn parse_i32(name: &str, value: &str) -> Result<i32, String> {
     value
         .parse()
         .map_err(|_| format!("{name} must be a valid i32, got {value:?}"))
 }
 
-fn parse_f32(name: &str, value: &str) -> Result<f32, String> {
+pub(crate) fn parse_f32(name: &str, value: &str) -> Result<f32, String> {
     value
         .parse()
         .map_err(|_| format!("{name} must be a valid f32, got {value:?}"))
 }
+
+/// Parses a `WIDTHxHEIGHT` size such as `800x600`.
+pub(crate) fn parse_size(name: &str, value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("{name} must be WIDTHxHEIGHT, got {value:?}"))?;
+    Ok((parse_extent(name, width)?, parse_extent(name, height)?))
+}
+
+/// Parses one side of a world or window size; the spawn layout needs at least 2.
+fn parse_extent(name: &str, value: &str) -> Result<u32, String> {
+    let extent = parse_u32(name, value)?;
+    if extent < 2 {
+        return Err(format!("{name} must be at least 2, got {extent}"));
+    }
+    Ok(extent)
+}
+
+pub(crate) fn parse_bool(name: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        _ => Err(format!(
+            "{name} must be one of 1, 0, true or false, got {value:?}"
+        )),
+    }
+}
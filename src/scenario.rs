@@ -0,0 +1,223 @@
+//! Scenario files: override the tunable simulation parameters without
+//! recompiling. Loaded from the `GATHERERS_SCENARIO` path as TOML or JSON,
+//! picked by file extension. Every key is optional; missing keys keep the
+//! `Config` defaults.
+//!
+//! ```toml
+//! food_count = 200
+//! ant_spawn_step = 25.0
+//! turn_angle_range = 1.2
+//! pickup_cooldown = 0.5
+//! collision_radius = 8.0
+//! spatial_cell_size = 16.0
+//! heatmap_cell_size = 50.0
+//! pheromone_deposit_rate = 1.0
+//! pickup_rule = "deneubourg"
+//! deneubourg_k1 = 0.1
+//! deneubourg_k2 = 0.3
+//! ```
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::config::{Config, PickupRule, SimulationSettings};
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Number of food items spawned
+    pub food_count: Option<u32>,
+    /// World width per spawned ant
+    pub ant_spawn_step: Option<f32>,
+    /// Maximum random deviation when an ant turns around (radians)
+    pub turn_angle_range: Option<f32>,
+    /// Cooldown after picking up or dropping food (seconds)
+    pub pickup_cooldown: Option<f32>,
+    /// Bounding radius of ants and food
+    pub collision_radius: Option<f32>,
+    /// Cell size of the collision spatial index
+    pub spatial_cell_size: Option<f32>,
+    /// Cell size of the food density heatmap overlay
+    pub heatmap_cell_size: Option<f32>,
+    /// Scent laid per second by an ant carrying food (0 disables pheromones)
+    pub pheromone_deposit_rate: Option<f32>,
+    /// Fraction of scent lost per second
+    pub pheromone_evaporation_rate: Option<f32>,
+    /// Fraction of scent shared with neighbouring cells per second
+    pub pheromone_diffusion_rate: Option<f32>,
+    /// Maximum turn rate towards stronger scent (radians per second)
+    pub pheromone_steering_rate: Option<f32>,
+    /// How an ant decides to pick up or drop food: `always` or `deneubourg`
+    pub pickup_rule: Option<PickupRule>,
+    /// Deneubourg pick-up constant
+    #[serde(alias = "k1")]
+    pub deneubourg_k1: Option<f32>,
+    /// Deneubourg drop constant
+    #[serde(alias = "k2")]
+    pub deneubourg_k2: Option<f32>,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read scenario {}: {err}", path.display()))?;
+        let scenario = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("json") => Self::from_json_str(&contents),
+            _ => Err("scenario file must end in .toml or .json".to_string()),
+        };
+        scenario.map_err(|err| format!("scenario {}: {err}", path.display()))
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Self, String> {
+        let scenario: Self = toml::from_str(contents).map_err(|err| err.to_string())?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn from_json_str(contents: &str) -> Result<Self, String> {
+        let scenario: Self = serde_json::from_str(contents).map_err(|err| err.to_string())?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// A scenario setting every value to the one in `settings`.
+    pub fn from_settings(settings: &SimulationSettings) -> Self {
+        Self {
+            food_count: Some(settings.food_count),
+            ant_spawn_step: Some(settings.ant_spawn_step),
+            turn_angle_range: Some(settings.turn_angle_range),
+            pickup_cooldown: Some(settings.pickup_cooldown),
+            collision_radius: Some(settings.base_collision_radius),
+            spatial_cell_size: Some(settings.spatial_cell_size),
+            heatmap_cell_size: Some(settings.heatmap_cell_size),
+            pheromone_deposit_rate: Some(settings.pheromone_deposit_rate),
+            pheromone_evaporation_rate: Some(settings.pheromone_evaporation_rate),
+            pheromone_diffusion_rate: Some(settings.pheromone_diffusion_rate),
+            pheromone_steering_rate: Some(settings.pheromone_steering_rate),
+            pickup_rule: Some(settings.pickup_rule),
+            deneubourg_k1: Some(settings.deneubourg_k1),
+            deneubourg_k2: Some(settings.deneubourg_k2),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(step) = self.ant_spawn_step {
+            require_positive("ant_spawn_step", step)?;
+        }
+        if let Some(range) = self.turn_angle_range
+            && !(0.0..=std::f32::consts::PI).contains(&range)
+        {
+            return Err(format!(
+                "turn_angle_range must be between 0 and pi radians, got {range}"
+            ));
+        }
+        if let Some(cooldown) = self.pickup_cooldown {
+            require_non_negative("pickup_cooldown", cooldown)?;
+        }
+        if let Some(radius) = self.collision_radius {
+            require_positive("collision_radius", radius)?;
+        }
+        if let Some(cell_size) = self.spatial_cell_size {
+            require_positive("spatial_cell_size", cell_size)?;
+        }
+        if let Some(cell_size) = self.heatmap_cell_size {
+            require_positive("heatmap_cell_size", cell_size)?;
+        }
+        for (name, rate) in [
+            ("pheromone_deposit_rate", self.pheromone_deposit_rate),
+            (
+                "pheromone_evaporation_rate",
+                self.pheromone_evaporation_rate,
+            ),
+            ("pheromone_diffusion_rate", self.pheromone_diffusion_rate),
+            ("pheromone_steering_rate", self.pheromone_steering_rate),
+        ] {
+            if let Some(rate) = rate {
+                require_non_negative(name, rate)?;
+            }
+        }
+        if let Some(k1) = self.deneubourg_k1 {
+            require_positive("deneubourg_k1", k1)?;
+        }
+        if let Some(k2) = self.deneubourg_k2 {
+            require_positive("deneubourg_k2", k2)?;
+        }
+
+        // Collisions are only checked against adjacent cells
+        let radius = self
+            .collision_radius
+            .unwrap_or(Config::BASE_COLLISION_RADIUS);
+        let cell_size = self.spatial_cell_size.unwrap_or(Config::SPATIAL_CELL_SIZE);
+        if cell_size < radius * 2.0 {
+            return Err(format!(
+                "spatial_cell_size ({cell_size}) must be at least twice collision_radius ({radius})"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Overrides the settings with every value the scenario sets.
+    pub fn apply(&self, settings: &mut SimulationSettings) {
+        if let Some(food_count) = self.food_count {
+            settings.food_count = food_count;
+        }
+        if let Some(step) = self.ant_spawn_step {
+            settings.ant_spawn_step = step;
+        }
+        if let Some(range) = self.turn_angle_range {
+            settings.turn_angle_range = range;
+        }
+        if let Some(cooldown) = self.pickup_cooldown {
+            settings.pickup_cooldown = cooldown;
+        }
+        if let Some(radius) = self.collision_radius {
+            settings.base_collision_radius = radius;
+        }
+        if let Some(cell_size) = self.spatial_cell_size {
+            settings.spatial_cell_size = cell_size;
+        }
+        if let Some(cell_size) = self.heatmap_cell_size {
+            settings.heatmap_cell_size = cell_size;
+        }
+        if let Some(rate) = self.pheromone_deposit_rate {
+            settings.pheromone_deposit_rate = rate;
+        }
+        if let Some(rate) = self.pheromone_evaporation_rate {
+            settings.pheromone_evaporation_rate = rate;
+        }
+        if let Some(rate) = self.pheromone_diffusion_rate {
+            settings.pheromone_diffusion_rate = rate;
+        }
+        if let Some(rate) = self.pheromone_steering_rate {
+            settings.pheromone_steering_rate = rate;
+        }
+        if let Some(rule) = self.pickup_rule {
+            settings.pickup_rule = rule;
+        }
+        if let Some(k1) = self.deneubourg_k1 {
+            settings.deneubourg_k1 = k1;
+        }
+        if let Some(k2) = self.deneubourg_k2 {
+            settings.deneubourg_k2 = k2;
+        }
+    }
+}
+
+fn require_positive(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{name} must be positive, got {value}"))
+    }
+}
+
+fn require_non_negative(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{name} must be zero or positive, got {value}"))
+    }
+}
@@ -0,0 +1,270 @@
+//! Fixed-timestep scheduling and seeded randomness for the simulation.
+//! Every system that changes simulation state runs in `FixedUpdate` in a fixed
+//! order and draws randomness from `SimRng`, so the same seed and tick count
+//! always reproduce the same pickup/drop event stream.
+//!
+//! The speed multiplier scales virtual time, so faster speeds run more fixed
+//! ticks per frame rather than longer, tunneling-prone steps.
+//!
+//! `SimControl` pauses the simulation, steps it one tick or runs it for a
+//! given number of ticks. Paused fixed ticks skip every `SimulationSet`.
+//! It can also reset the world with a new seed: `reset_simulation` clears it
+//! and writes `SimReset` so whoever spawned it spawns it again.
+
+use bevy::prelude::*;
+use bevy::time::TimeSystems;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::collision::HitEvent;
+use crate::colony::Colony;
+use crate::config::{Config, SimulationSettings};
+use crate::net::{BackendAntSlots, BackendFoodSlots, BackendSimEvent};
+use crate::spatial_index::SpatialIndex;
+use crate::{Ant, Food, ant_hits_system, cooldown_system, gatherer_movement};
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulationSettings>()
+            .init_resource::<SimRng>()
+            .init_resource::<SimTick>()
+            .init_resource::<SimControl>()
+            .init_resource::<AchievedTickRate>()
+            .init_resource::<SpatialIndex>()
+            .insert_resource(Time::<Fixed>::from_hz(Config::SIM_TICK_HZ))
+            .add_message::<HitEvent<Food, Ant>>()
+            .add_message::<BackendSimEvent>()
+            .add_message::<SimReset>()
+            .configure_sets(
+                FixedUpdate,
+                (
+                    SimulationSet::Tick,
+                    SimulationSet::Movement,
+                    SimulationSet::Boundary,
+                    SimulationSet::Collision,
+                    SimulationSet::Hits,
+                    SimulationSet::Cooldown,
+                    SimulationSet::Pheromone,
+                )
+                    .chain()
+                    .run_if(sim_ticking),
+            )
+            .add_systems(FixedUpdate, advance_sim_control.before(SimulationSet::Tick))
+            .add_systems(FixedUpdate, sim_tick_system.in_set(SimulationSet::Tick))
+            .add_systems(
+                FixedUpdate,
+                gatherer_movement.in_set(SimulationSet::Movement),
+            )
+            .add_systems(FixedUpdate, ant_hits_system.in_set(SimulationSet::Hits))
+            .add_systems(FixedUpdate, cooldown_system.in_set(SimulationSet::Cooldown))
+            .add_systems(First, apply_time_scale.before(TimeSystems))
+            .add_systems(PreUpdate, reset_simulation)
+            .add_systems(Last, measure_tick_rate);
+    }
+}
+
+/// Ordered stages of one simulation tick in `FixedUpdate`.
+/// Plugins that touch simulation state put their systems in one of these sets.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    Tick,
+    Movement,
+    Boundary,
+    Collision,
+    Hits,
+    Cooldown,
+    Pheromone,
+}
+
+/// Number of simulation ticks run so far.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimTick(pub u64);
+
+/// Whether the simulation is running, paused or running a fixed number of
+/// ticks before pausing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    #[default]
+    Running,
+    Paused,
+    /// Ticks left before pausing
+    Ticks(u64),
+}
+
+/// Pause, single-step and run-for-N-ticks control. The UI drives it from the
+/// keyboard; tests and other plugins can drive it directly.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimControl {
+    mode: RunMode,
+    /// Decided once at the start of each fixed tick so every set agrees
+    ticking: bool,
+    /// Seed of a requested world reset, applied by `reset_simulation`
+    reset_seed: Option<u64>,
+}
+
+impl SimControl {
+    pub fn mode(&self) -> RunMode {
+        self.mode
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.mode == RunMode::Paused
+    }
+
+    pub fn pause(&mut self) {
+        self.mode = RunMode::Paused;
+    }
+
+    pub fn resume(&mut self) {
+        self.mode = RunMode::Running;
+    }
+
+    /// Resumes when paused, otherwise pauses (also cutting a tick run short).
+    pub fn toggle_pause(&mut self) {
+        if self.is_paused() {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Advances exactly one tick, then pauses.
+    pub fn step(&mut self) {
+        self.run_ticks(1);
+    }
+
+    /// Runs `ticks` more ticks, then pauses.
+    pub fn run_ticks(&mut self, ticks: u64) {
+        self.mode = if ticks == 0 {
+            RunMode::Paused
+        } else {
+            RunMode::Ticks(ticks)
+        };
+    }
+
+    /// Respawns the world from `seed` at the start of the next frame.
+    pub fn request_reset(&mut self, seed: u64) {
+        self.reset_seed = Some(seed);
+    }
+
+    pub fn reset_requested(&self) -> Option<u64> {
+        self.reset_seed
+    }
+}
+
+/// Written after `reset_simulation` despawned the ants, food and nests and
+/// restarted the tick count and `SimRng` from `seed`. Whoever spawned the
+/// world spawns it again from the same seed.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimReset {
+    pub seed: u64,
+}
+
+/// Simulation ticks actually run per real second, measured over a short
+/// window. Lower than the nominal rate when the machine can't keep up.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct AchievedTickRate {
+    ticks_per_second: f32,
+    window_start_tick: u64,
+    window_secs: f32,
+}
+
+impl AchievedTickRate {
+    pub fn ticks_per_second(&self) -> f32 {
+        self.ticks_per_second
+    }
+}
+
+/// The single source of randomness for simulation decisions after spawn.
+#[derive(Resource, Deref, DerefMut)]
+pub struct SimRng(StdRng);
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::from_seed(None)
+    }
+}
+
+impl SimRng {
+    /// Seeded from `GATHERERS_SIM_SEED` when given. The seed is offset so the
+    /// turn decisions don't replay the random sequence of the spawn layout.
+    pub fn from_seed(seed: Option<u64>) -> Self {
+        match seed {
+            Some(seed) => Self(StdRng::seed_from_u64(seed.wrapping_add(1))),
+            None => Self(StdRng::from_os_rng()),
+        }
+    }
+}
+
+/// Clears the world when `SimControl` has a reset pending. Plugins with
+/// per-world state reset it on `SimReset`.
+pub fn reset_simulation(world: &mut World) {
+    let Some(seed) = world.resource_mut::<SimControl>().reset_seed.take() else {
+        return;
+    };
+
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Ant>, With<Food>, With<Colony>)>>()
+        .iter(world)
+        .collect();
+    for entity in entities {
+        // Carried food already went with its ant
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn();
+        }
+    }
+
+    world.resource_mut::<SimTick>().0 = 0;
+    world.insert_resource(SimRng::from_seed(Some(seed)));
+    world.insert_resource(AchievedTickRate::default());
+    let mut spatial_index = world.resource_mut::<SpatialIndex>();
+    *spatial_index = SpatialIndex::new(spatial_index.cell_size());
+    // Backend ids restart at 0 for the new world, before any new ant is added
+    if world.contains_resource::<BackendAntSlots>() {
+        world.insert_resource(BackendAntSlots::default());
+        world.insert_resource(BackendFoodSlots::default());
+    }
+
+    info!("Reset the simulation with seed {seed}");
+    world.write_message(SimReset { seed });
+}
+
+fn advance_sim_control(mut control: ResMut<SimControl>) {
+    control.ticking = match control.mode {
+        RunMode::Running => true,
+        RunMode::Paused => false,
+        RunMode::Ticks(ticks) => {
+            control.run_ticks(ticks - 1);
+            true
+        }
+    };
+}
+
+fn sim_ticking(control: Res<SimControl>) -> bool {
+    control.ticking
+}
+
+fn sim_tick_system(mut tick: ResMut<SimTick>) {
+    tick.0 += 1;
+}
+
+fn apply_time_scale(settings: Res<SimulationSettings>, mut time: ResMut<Time<Virtual>>) {
+    let scale = settings.time_scale();
+    if time.relative_speed() != scale {
+        time.set_relative_speed(scale);
+    }
+}
+
+fn measure_tick_rate(
+    real_time: Res<Time<Real>>,
+    tick: Res<SimTick>,
+    mut rate: ResMut<AchievedTickRate>,
+) {
+    rate.window_secs += real_time.delta_secs();
+    if rate.window_secs >= Config::TICK_RATE_WINDOW_SECS {
+        rate.ticks_per_second = (tick.0 - rate.window_start_tick) as f32 / rate.window_secs;
+        rate.window_start_tick = tick.0;
+        rate.window_secs = 0.0;
+    }
+}
@@ -4,35 +4,78 @@ use crate::config::Config;
 use bevy::prelude::*;
 use std::collections::{HashMap, HashSet};
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct SpatialIndex {
     map: HashMap<(i32, i32), HashSet<Entity>>,
+    cell_size: f32,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(Config::SPATIAL_CELL_SIZE)
+    }
 }
 
 impl SpatialIndex {
-    // Lookup all entities within adjacent cells of our spatial index
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            map: HashMap::new(),
+            cell_size,
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    // Lookup all entities within adjacent cells of our spatial index.
+    // Sorted so callers that stop at the first hit behave the same on every run.
     pub fn get_nearby(&self, pos: Vec2) -> Vec<Entity> {
-        let tile = (
-            (pos.x / Config::SPATIAL_CELL_SIZE).floor() as i32,
-            (pos.y / Config::SPATIAL_CELL_SIZE).floor() as i32,
-        );
         let mut nearby = Vec::new();
-        for x in -1..2 {
-            for y in -1..2 {
-                if let Some(mines) = self.map.get(&(tile.0 + x, tile.1 + y)) {
-                    nearby.extend(mines.iter());
-                }
-            }
+        self.extend_with_adjacent(self.tile_of(pos), &mut nearby);
+        nearby.sort_unstable();
+        nearby
+    }
+
+    // Lookup all entities within cells adjacent to any cell the segment crosses.
+    // Samples every half cell, so each crossed cell is next to a sampled one.
+    pub fn get_along_segment(&self, start: Vec2, end: Vec2) -> Vec<Entity> {
+        let steps = ((end - start).length() / (self.cell_size * 0.5)).ceil() as usize;
+        let mut tiles = HashSet::new();
+        for step in 0..=steps {
+            let t = if steps == 0 {
+                0.0
+            } else {
+                step as f32 / steps as f32
+            };
+            tiles.insert(self.tile_of(start.lerp(end, t)));
         }
+
+        let mut nearby = Vec::new();
+        for tile in tiles {
+            self.extend_with_adjacent(tile, &mut nearby);
+        }
+        nearby.sort_unstable();
+        nearby.dedup();
         nearby
     }
 
+    // Local density in [0, 1]: entities in the adjacent cells, not counting
+    // `exclude`, relative to Config::FOOD_DENSITY_SATURATION.
+    pub fn density_at(&self, pos: Vec2, exclude: Option<Entity>) -> f32 {
+        let count = self
+            .get_nearby(pos)
+            .into_iter()
+            .filter(|entity| Some(*entity) != exclude)
+            .count();
+        (count as f32 / Config::FOOD_DENSITY_SATURATION).min(1.0)
+    }
+
     pub fn update(&mut self, entity: Entity, pos: Vec2) {
-        let tile = (
-            (pos.x / Config::SPATIAL_CELL_SIZE).floor() as i32,
-            (pos.y / Config::SPATIAL_CELL_SIZE).floor() as i32,
-        );
-        self.map.entry(tile).or_default().insert(entity);
+        self.map
+            .entry(self.tile_of(pos))
+            .or_default()
+            .insert(entity);
     }
 
     pub fn remove(&mut self, entity: Entity) {
@@ -40,4 +83,21 @@ impl SpatialIndex {
             mines.remove(&entity);
         }
     }
+
+    fn extend_with_adjacent(&self, tile: (i32, i32), nearby: &mut Vec<Entity>) {
+        for x in -1..2 {
+            for y in -1..2 {
+                if let Some(mines) = self.map.get(&(tile.0 + x, tile.1 + y)) {
+                    nearby.extend(mines.iter());
+                }
+            }
+        }
+    }
+
+    fn tile_of(&self, pos: Vec2) -> (i32, i32) {
+        (
+            (pos.x / self.cell_size).floor() as i32,
+            (pos.y / self.cell_size).floor() as i32,
+        )
+    }
 }
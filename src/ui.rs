@@ -1,15 +1,28 @@
 //! UI system for the gatherers simulation
 //! Handles speed control via keyboard and other UI elements
 
+use crate::analytics::{AnalyticsPlugin, ClusteringHistory, ClusteringMetric, ClusteringStats};
 use crate::config::{Config, SimulationSettings};
+use crate::simulation::{AchievedTickRate, RunMode, SimControl};
 use bevy::prelude::*;
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_ui)
-            .add_systems(Update, (handle_keyboard_input, update_speed_display).chain());
+        app.add_plugins(AnalyticsPlugin)
+            .add_systems(
+                Startup,
+                (setup_ui, setup_clustering_panel, setup_clustering_charts),
+            )
+            .add_systems(
+                Update,
+                (handle_keyboard_input, update_speed_display).chain(),
+            )
+            .add_systems(
+                Update,
+                (update_clustering_display, update_clustering_charts),
+            );
     }
 }
 
@@ -19,6 +32,22 @@ pub struct SpeedLabel;
 #[derive(Component)]
 pub struct SpeedBar;
 
+#[derive(Component)]
+pub struct ClusteringLabel;
+
+/// Title of a clustering chart, showing the metric's range.
+#[derive(Component)]
+pub struct ChartLabel(pub ClusteringMetric);
+
+/// Plot area of a clustering chart; its children are the line segments.
+#[derive(Component)]
+pub struct ChartPlot(pub ClusteringMetric);
+
+/// One line segment of a chart, joining two consecutive samples. Each plot
+/// owns a fixed pool of them that is moved around in place.
+#[derive(Component)]
+pub struct ChartSegment;
+
 pub fn setup_ui(mut commands: Commands) {
     // Root UI container
     commands
@@ -27,13 +56,14 @@ pub fn setup_ui(mut commands: Commands) {
             top: Val::Px(10.0),
             left: Val::Px(10.0),
             width: Val::Px(350.0),
-            height: Val::Px(120.0),
             flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::SpaceBetween,
             padding: UiRect::all(Val::Px(15.0)),
+            row_gap: Val::Px(6.0),
             ..default()
         })
         .insert(BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)))
+        // Tracks hovering so mouse clicks on the panel don't edit the world
+        .insert(Interaction::default())
         .with_children(|parent| {
             // Speed label
             parent.spawn((
@@ -98,7 +128,66 @@ pub fn setup_ui(mut commands: Commands) {
             ));
 
             parent.spawn((
-                Text::new("[U] Max Safe Speed"),
+                Text::new("[U] Toggle max speed"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+
+            parent.spawn((
+                Text::new(format!(
+                    "[Space] Pause  [.] Step  [N] Run {} ticks",
+                    Config::RUN_TICKS_STEP
+                )),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+
+            parent.spawn((
+                Text::new("[H] Food heatmap  [F] Fit world"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+        });
+}
+
+/// Live clustering metrics, to the right of the speed panel.
+pub fn setup_clustering_panel(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(370.0),
+            width: Val::Px(260.0),
+            height: Val::Px(120.0),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(15.0)),
+            row_gap: Val::Px(6.0),
+            ..default()
+        })
+        .insert(BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)))
+        .insert(Interaction::default())
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Clustering"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent.spawn((
+                ClusteringLabel,
+                Text::new(""),
                 TextFont {
                     font_size: 14.0,
                     ..default()
@@ -108,10 +197,88 @@ pub fn setup_ui(mut commands: Commands) {
         });
 }
 
+/// Line charts of the clustering metrics over sim time, in the bottom-left
+/// corner so they stay clear of the panels above however tall those grow.
+pub fn setup_clustering_charts(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            width: Val::Px(620.0),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(15.0)),
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .insert(BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)))
+        .insert(Interaction::default())
+        .with_children(|parent| {
+            for metric in ClusteringMetric::ALL {
+                parent.spawn((
+                    ChartLabel(metric),
+                    Text::new(metric.label()),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(chart_color(metric)),
+                ));
+
+                parent
+                    .spawn((
+                        ChartPlot(metric),
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(40.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    ))
+                    .with_children(|plot| {
+                        for _ in 1..Config::ANALYTICS_HISTORY_LEN {
+                            plot.spawn((
+                                ChartSegment,
+                                Node {
+                                    position_type: PositionType::Absolute,
+                                    height: Val::Px(Config::CHART_LINE_WIDTH),
+                                    ..default()
+                                },
+                                UiTransform::default(),
+                                BackgroundColor(chart_color(metric)),
+                                Visibility::Hidden,
+                            ));
+                        }
+                    });
+            }
+        });
+}
+
+fn chart_color(metric: ClusteringMetric) -> Color {
+    match metric {
+        ClusteringMetric::LooseFood => Color::srgb(0.9, 0.7, 0.2),
+        ClusteringMetric::OccupiedCells => Color::srgb(0.3, 0.7, 1.0),
+        ClusteringMetric::NearestNeighborDistance => Color::srgb(0.4, 0.9, 0.4),
+    }
+}
+
 fn handle_keyboard_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut settings: ResMut<SimulationSettings>,
+    mut control: ResMut<SimControl>,
 ) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        control.toggle_pause();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Period) {
+        control.step();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        control.run_ticks(Config::RUN_TICKS_STEP);
+    }
+
     let mut new_speed = settings.speed_multiplier;
 
     if keyboard_input.just_pressed(KeyCode::Minus) {
@@ -138,23 +305,27 @@ fn handle_keyboard_input(
 }
 
 fn update_speed_display(
-    time: Res<Time>,
     settings: Res<SimulationSettings>,
+    tick_rate: Res<AchievedTickRate>,
+    control: Res<SimControl>,
     mut label_query: Query<&mut Text, With<SpeedLabel>>,
     mut bar_query: Query<&mut Node, With<SpeedBar>>,
 ) {
-    let delta = time.delta_secs();
+    let ticks_per_second = tick_rate.ticks_per_second();
 
     if let Ok(mut text) = label_query.single_mut() {
-        text.0 = if settings.is_unlimited_speed() {
-            if delta > f32::EPSILON {
-                let mult = settings.effective_multiplier(delta);
-                format!("Speed: MAX (~{:.1}x)", mult)
-            } else {
-                "Speed: MAX".to_string()
-            }
+        let speed = if settings.is_unlimited_speed() {
+            format!("Speed: MAX (~{:.0} ticks/s)", ticks_per_second)
         } else {
-            format!("Speed: {:.1}x", settings.speed_multiplier)
+            format!(
+                "Speed: {:.1}x ({:.0} ticks/s)",
+                settings.speed_multiplier, ticks_per_second
+            )
+        };
+        text.0 = match control.mode() {
+            RunMode::Running => speed,
+            RunMode::Paused => format!("{speed} PAUSED"),
+            RunMode::Ticks(ticks) => format!("{speed} {ticks} to go"),
         };
     }
 
@@ -169,3 +340,70 @@ fn update_speed_display(
         }
     }
 }
+
+fn update_clustering_display(
+    stats: Res<ClusteringStats>,
+    mut label_query: Query<&mut Text, With<ClusteringLabel>>,
+) {
+    if !stats.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = label_query.single_mut() {
+        let summary = &stats.summary;
+        text.0 = format!(
+            "Loose food: {}\nOccupied cells: {}\nNearest neighbour: {:.1}\nat tick {}",
+            summary.loose_food_count,
+            summary.occupied_cell_count,
+            summary.nearest_neighbor_mean_distance,
+            stats.tick
+        );
+    }
+}
+
+fn update_clustering_charts(
+    history: Res<ClusteringHistory>,
+    plots: Query<(&ChartPlot, Ref<ComputedNode>, &Children)>,
+    mut segments: Query<(&mut Node, &mut UiTransform, &mut Visibility), With<ChartSegment>>,
+    mut labels: Query<(&mut Text, &ChartLabel)>,
+) {
+    if history.is_changed() {
+        for (mut text, ChartLabel(metric)) in &mut labels {
+            if let Some((min, max)) = history.range(*metric) {
+                text.0 = format!("{}: {:.1} - {:.1}", metric.label(), min, max);
+            }
+        }
+    }
+
+    for (ChartPlot(metric), computed, children) in &plots {
+        // The plot size is only known after layout, and changes with the window
+        if !history.is_changed() && !computed.is_changed() {
+            continue;
+        }
+        let size = computed.size() * computed.inverse_scale_factor();
+        // UI y grows downwards
+        let points: Vec<Vec2> = history
+            .normalized(*metric)
+            .into_iter()
+            .map(|point| Vec2::new(point.x, 1.0 - point.y) * size)
+            .collect();
+
+        for (index, child) in children.iter().enumerate() {
+            let Ok((mut node, mut transform, mut visibility)) = segments.get_mut(child) else {
+                continue;
+            };
+            let (Some(&start), Some(&end)) = (points.get(index), points.get(index + 1)) else {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            };
+            // A horizontal bar centred between the samples, rotated onto them
+            let delta = end - start;
+            let center = (start + end) / 2.0;
+            let length = delta.length();
+            node.left = Val::Px(center.x - length / 2.0);
+            node.top = Val::Px(center.y - Config::CHART_LINE_WIDTH / 2.0);
+            node.width = Val::Px(length);
+            transform.rotation = Rot2::radians(delta.y.atan2(delta.x));
+            visibility.set_if_neq(Visibility::Inherited);
+        }
+    }
+}
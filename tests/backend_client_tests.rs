@@ -2,6 +2,7 @@ use an_gatherers::collision::{initialize_hittables, update_hittable_positions};
 use an_gatherers::spatial_index::SpatialIndex;
 use an_gatherers::*;
 use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
 use serde_json::Value;
 
 #[test]
@@ -12,6 +13,7 @@ fn test_pickup_collision_queues_backend_messages() {
         .init_resource::<SpatialIndex>()
         .add_message::<HitEvent<Food, Ant>>()
         .insert_resource(SimulationSettings::default())
+        .init_resource::<SimRng>()
         .insert_resource(BackendClientConfig::enabled(
             "ws://localhost:8080/ws/ingest".to_string(),
             "sim-test".to_string(),
@@ -86,6 +88,11 @@ fn test_pickup_collision_queues_backend_messages() {
         Some(0),
         "expected food_pickup to use dense numeric slot ids, got {pickup_json:?}"
     );
+    assert_eq!(
+        pickup_json["payload"]["ant_id"].as_str(),
+        Some("0"),
+        "expected food_pickup to use the dense ant id, got {pickup_json:?}"
+    );
 
     let has_children = app
         .world()
@@ -104,6 +111,7 @@ fn test_startup_queues_food_snapshot_after_hello() {
             "ws://localhost:8080/ws/ingest".to_string(),
             "sim-startup".to_string(),
         ))
+        .insert_resource(WorldBounds::new(Vec2::new(2000.0, 1000.0)))
         .add_plugins(BackendClientPlugin);
 
     let world = app.world_mut();
@@ -133,6 +141,16 @@ fn test_startup_queues_food_snapshot_after_hello() {
         "expected first startup message to be sim_hello, got {:?}",
         queued
     );
+    let hello_json: Value =
+        serde_json::from_str(&queued[0]).expect("hello message should be valid json");
+    assert_eq!(
+        (
+            hello_json["payload"]["world_width"].as_f64(),
+            hello_json["payload"]["world_height"].as_f64()
+        ),
+        (Some(2000.0), Some(1000.0)),
+        "expected sim_hello to report the WorldBounds size, got {hello_json:?}"
+    );
     assert!(
         queued[1].contains("\"type\":\"sim_food_snapshot\""),
         "expected second startup message to be sim_food_snapshot, got {:?}",
@@ -174,3 +192,471 @@ fn test_startup_queues_food_snapshot_after_hello() {
         "expected startup snapshot food ids to be dense numeric slots, got {snapshot_json:?}"
     );
 }
+
+#[test]
+fn test_food_snapshot_leaves_carried_food_out_of_its_slots() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(BackendClientConfig::enabled(
+            "ws://localhost:8080/ws/ingest".to_string(),
+            "sim-carried".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+
+    let world = app.world_mut();
+    let ant = world.spawn((Ant, Transform::default())).id();
+    let carried = world.spawn((Food, Transform::default())).id();
+    world.entity_mut(ant).add_child(carried);
+    world.spawn((Food, Transform::from_xyz(10.0, 0.0, 0.0)));
+    app.update();
+
+    let snapshots = queued_messages_of_type(&app, "sim_food_snapshot");
+    let payload = &snapshots[0]["payload"];
+    assert_eq!(payload["slot_count"].as_u64(), Some(2));
+    let foods = payload["foods"].as_array().expect("foods array");
+    assert_eq!(foods.len(), 1, "got {payload:?}");
+    assert_eq!(foods[0]["x"].as_f64(), Some(10.0));
+}
+
+#[test]
+fn test_startup_queues_ant_snapshot_with_dense_ids_in_spawn_order() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(BackendClientConfig::enabled(
+            "ws://localhost:8080/ws/ingest".to_string(),
+            "sim-ants".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+
+    let world = app.world_mut();
+    world.spawn((Food, Transform::default()));
+    world.spawn((
+        Ant,
+        ColonyId(1),
+        Transform::from_translation(Vec3::new(-5.0, 7.0, 0.0)),
+    ));
+    world.spawn((Ant, Transform::from_translation(Vec3::new(15.0, 25.0, 0.0))));
+
+    app.update();
+
+    let queued = app
+        .world()
+        .resource::<PendingBackendEvents>()
+        .queued_json_messages();
+    assert!(
+        queued.len() >= 3 && queued[2].contains("\"type\":\"sim_ant_snapshot\""),
+        "expected sim_ant_snapshot after the food snapshot, got {:?}",
+        queued
+    );
+
+    let snapshot_json: Value =
+        serde_json::from_str(&queued[2]).expect("ant snapshot should be valid json");
+    let ants: Vec<(String, u64, f64)> = snapshot_json["payload"]["ants"]
+        .as_array()
+        .expect("ants array should be present")
+        .iter()
+        .map(|ant| {
+            (
+                ant["ant_id"].as_str().unwrap().to_string(),
+                ant["colony_id"].as_u64().unwrap(),
+                ant["x"].as_f64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ants,
+        vec![("0".to_string(), 1, -5.0), ("1".to_string(), 0, 15.0)],
+        "expected dense ant ids in spawn order, got {snapshot_json:?}"
+    );
+}
+
+#[test]
+fn test_spawned_and_deleted_ants_queue_a_fresh_ant_snapshot() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(BackendClientConfig::enabled(
+            "ws://localhost:8080/ws/ingest".to_string(),
+            "sim-ant-edits".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+
+    let world = app.world_mut();
+    world.spawn((Food, Transform::default()));
+    let first = world.spawn((Ant, Transform::default())).id();
+    app.update();
+    app.update();
+
+    let snapshot_sizes = |app: &App| -> Vec<usize> {
+        queued_messages_of_type(app, "sim_ant_snapshot")
+            .iter()
+            .map(|snapshot| snapshot["payload"]["ants"].as_array().unwrap().len())
+            .collect()
+    };
+    assert_eq!(snapshot_sizes(&app), vec![1], "one startup snapshot");
+
+    app.world_mut().spawn((Ant, Transform::default()));
+    app.world_mut().spawn((Ant, Transform::default()));
+    app.update();
+    app.world_mut().despawn(first);
+    app.update();
+    app.update();
+
+    assert_eq!(snapshot_sizes(&app), vec![1, 3, 2]);
+}
+
+fn queued_messages_of_type(app: &App, event_type: &str) -> Vec<Value> {
+    app.world()
+        .resource::<PendingBackendEvents>()
+        .queued_json_messages()
+        .iter()
+        .map(|msg| serde_json::from_str::<Value>(msg).expect("queued message should be valid json"))
+        .filter(|msg| msg["type"] == event_type)
+        .collect()
+}
+
+#[test]
+fn test_pickup_and_drop_carry_increasing_frames_and_timestamps() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+        .insert_resource(SimulationSettings::default())
+        .insert_resource(SimRng::from_seed(Some(5)))
+        .insert_resource(BackendClientConfig::enabled(
+            "ws://localhost:8080/ws/ingest".to_string(),
+            "sim-frames".to_string(),
+        ))
+        .add_plugins(SimulationPlugin)
+        .add_plugins(CollisionPlugin::<Food, Ant>::new())
+        .add_plugins(BackendClientPlugin);
+
+    let world = app.world_mut();
+    let ant = world
+        .spawn((
+            Ant,
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(0.0, 0.0, Config::ANT_Z_LAYER)),
+            Bounding::from_radius(5.0),
+            Collidable,
+        ))
+        .id();
+    world.spawn((
+        Food,
+        Transform::from_translation(Vec3::new(2.0, 0.0, Config::FOOD_Z_LAYER)),
+        Bounding::from_radius(5.0),
+        Collidable,
+    ));
+
+    for _ in 0..5 {
+        app.update();
+        if !queued_messages_of_type(&app, "food_pickup").is_empty() {
+            break;
+        }
+    }
+    assert_eq!(queued_messages_of_type(&app, "food_pickup").len(), 1);
+
+    // Put a second food item under the laden ant so it drops on a later tick
+    let ant_position = app
+        .world()
+        .entity(ant)
+        .get::<Transform>()
+        .unwrap()
+        .translation;
+    let food = app
+        .world_mut()
+        .spawn((
+            Food,
+            Transform::from_translation(ant_position.with_z(Config::FOOD_Z_LAYER)),
+            Bounding::from_radius(5.0),
+            Collidable,
+        ))
+        .id();
+    app.world_mut()
+        .resource_mut::<SpatialIndex>()
+        .update(food, ant_position.truncate());
+    for _ in 0..5 {
+        app.update();
+        if !queued_messages_of_type(&app, "food_drop").is_empty() {
+            break;
+        }
+    }
+
+    let pickup = &queued_messages_of_type(&app, "food_pickup")[0];
+    let drops = queued_messages_of_type(&app, "food_drop");
+    assert_eq!(drops.len(), 1, "expected the laden ant to drop its food");
+    let pickup_frame = pickup["payload"]["frame"].as_u64().unwrap();
+    let drop_frame = drops[0]["payload"]["frame"].as_u64().unwrap();
+    assert!(
+        pickup_frame > 0,
+        "expected a real tick number, got {pickup:?}"
+    );
+    assert!(
+        drop_frame > pickup_frame,
+        "expected the drop on a later tick than the pickup, got {pickup_frame} and {drop_frame}"
+    );
+
+    let hello = &queued_messages_of_type(&app, "sim_hello")[0];
+    let session_started_ms = hello["payload"]["session_started_ms"].as_u64().unwrap();
+    assert!(
+        session_started_ms > 0,
+        "expected a wall-clock session start, got {hello:?}"
+    );
+    let timestamps: Vec<u64> = app
+        .world()
+        .resource::<PendingBackendEvents>()
+        .queued_json_messages()
+        .iter()
+        .map(|msg| {
+            serde_json::from_str::<Value>(msg).unwrap()["timestamp_ms"]
+                .as_u64()
+                .unwrap()
+        })
+        .collect();
+    assert!(
+        timestamps[0] >= session_started_ms && timestamps.is_sorted(),
+        "expected timestamps from the session start onwards, got {timestamps:?}"
+    );
+}
+
+#[test]
+fn test_heartbeats_follow_the_configured_interval_with_real_counts() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_millis(400),
+        ))
+        .insert_resource(BackendClientConfig {
+            heartbeat_interval_secs: 1.0,
+            ..BackendClientConfig::enabled(
+                "ws://localhost:8080/ws/ingest".to_string(),
+                "sim-heartbeat".to_string(),
+            )
+        })
+        .add_plugins(BackendClientPlugin);
+
+    let world = app.world_mut();
+    let ant = world.spawn((Ant, Transform::default())).id();
+    let carried = world.spawn((Food, Transform::default())).id();
+    world.entity_mut(ant).add_child(carried);
+    world.spawn((Food, Transform::from_xyz(10.0, 0.0, 0.0)));
+    world.spawn((Food, Transform::from_xyz(20.0, 0.0, 0.0)));
+
+    // 0.4 s per update: startup, then heartbeats at 1.2 s and 2.4 s
+    for _ in 0..7 {
+        app.update();
+    }
+
+    let heartbeats = queued_messages_of_type(&app, "sim_heartbeat");
+    assert_eq!(heartbeats.len(), 2, "got {heartbeats:?}");
+    let payload = &heartbeats[0]["payload"];
+    assert_eq!(payload["connected_ant_count"].as_u64(), Some(1));
+    assert_eq!(payload["known_food_count"].as_u64(), Some(3));
+    assert_eq!(payload["dropped_outbound_events"].as_u64(), Some(0));
+}
+
+/// Runs two heartbeat intervals with an outbound queue that fits `capacity`
+/// messages.
+fn run_with_full_outbound_queue(policy: BackendDropPolicy, capacity: usize) -> App {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_millis(400),
+        ))
+        .insert_resource(BackendClientConfig {
+            heartbeat_interval_secs: 1.0,
+            outbound_capacity: capacity,
+            outbound_drop_policy: policy,
+            ..BackendClientConfig::enabled(
+                "ws://localhost:8080/ws/ingest".to_string(),
+                "sim-full-queue".to_string(),
+            )
+        })
+        .add_plugins(BackendClientPlugin);
+
+    for _ in 0..7 {
+        app.update();
+    }
+    app
+}
+
+fn queued_types(app: &App) -> Vec<String> {
+    app.world()
+        .resource::<PendingBackendEvents>()
+        .queued_json_messages()
+        .iter()
+        .map(|message| {
+            let value: Value = serde_json::from_str(message).expect("queued message is JSON");
+            value["type"].as_str().unwrap_or_default().to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_full_outbound_queue_drops_newest_by_default() {
+    let app = run_with_full_outbound_queue(BackendDropPolicy::default(), 3);
+
+    assert_eq!(
+        queued_types(&app),
+        ["sim_hello", "sim_food_snapshot", "sim_ant_snapshot"]
+    );
+    assert_eq!(app.world().resource::<BackendDroppedEvents>().0, 2);
+}
+
+#[test]
+fn test_full_outbound_queue_can_drop_oldest_and_reports_drops() {
+    let app = run_with_full_outbound_queue(BackendDropPolicy::DropOldest, 4);
+
+    assert_eq!(
+        queued_types(&app),
+        [
+            "sim_hello",
+            "sim_food_snapshot",
+            "sim_ant_snapshot",
+            "sim_heartbeat"
+        ]
+    );
+    assert_eq!(app.world().resource::<BackendDroppedEvents>().0, 1);
+    // The first heartbeat made room for the second
+    let heartbeats = queued_messages_of_type(&app, "sim_heartbeat");
+    assert_eq!(heartbeats[0]["seq"], 5);
+}
+
+#[test]
+fn test_drop_oldest_never_evicts_session_messages() {
+    let app = run_with_full_outbound_queue(BackendDropPolicy::DropOldest, 3);
+
+    assert_eq!(
+        queued_types(&app),
+        ["sim_hello", "sim_food_snapshot", "sim_ant_snapshot"]
+    );
+    assert_eq!(app.world().resource::<BackendDroppedEvents>().0, 2);
+}
+
+#[test]
+fn test_session_messages_are_queued_past_capacity() {
+    let app = run_with_full_outbound_queue(BackendDropPolicy::DropOldest, 1);
+
+    assert_eq!(
+        queued_types(&app),
+        ["sim_hello", "sim_food_snapshot", "sim_ant_snapshot"]
+    );
+    assert_eq!(app.world().resource::<BackendDroppedEvents>().0, 2);
+}
+
+#[test]
+fn test_app_exit_queues_a_single_goodbye_last() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(BackendClientConfig::enabled(
+            "ws://localhost:8080/ws/ingest".to_string(),
+            "sim-goodbye".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+    app.update();
+    assert!(queued_messages_of_type(&app, "sim_goodbye").is_empty());
+
+    app.world_mut().write_message(AppExit::Success);
+    app.update();
+    app.world_mut().write_message(AppExit::Success);
+    app.update();
+
+    let goodbyes = queued_messages_of_type(&app, "sim_goodbye");
+    assert_eq!(goodbyes.len(), 1, "got {goodbyes:?}");
+    assert_eq!(goodbyes[0]["payload"]["reason"], "shutdown");
+    let queued = app
+        .world()
+        .resource::<PendingBackendEvents>()
+        .queued_json_messages();
+    assert!(
+        queued.last().unwrap().contains("\"type\":\"sim_goodbye\""),
+        "expected sim_goodbye to be the last queued message, got {queued:?}"
+    );
+}
+
+#[test]
+fn test_goodbye_is_queued_even_when_the_queue_is_full() {
+    let mut app = run_with_full_outbound_queue(BackendDropPolicy::default(), 3);
+
+    app.world_mut().write_message(AppExit::Success);
+    app.update();
+
+    assert_eq!(
+        queued_types(&app).last().map(String::as_str),
+        Some("sim_goodbye")
+    );
+}
+
+#[test]
+fn test_parse_backend_command_reads_sim_command_messages() {
+    assert_eq!(
+        parse_backend_command(
+            r#"{"type":"sim_command","sim_id":"sim-a","payload":{"command":"set_speed","speed":2.5}}"#
+        ),
+        Ok(BackendCommand::SetSpeed { speed: 2.5 })
+    );
+    assert_eq!(
+        parse_backend_command(
+            r#"{"type":"sim_command","sim_id":"sim-a","payload":{"command":"reset","seed":7}}"#
+        ),
+        Ok(BackendCommand::Reset { seed: 7 })
+    );
+    assert!(
+        parse_backend_command(r#"{"type":"sim_command","payload":{"command":"explode"}}"#).is_err()
+    );
+    assert!(
+        parse_backend_command(r#"{"type":"dashboard","payload":{"command":"pause"}}"#).is_err()
+    );
+}
+
+#[test]
+fn test_backend_commands_drive_settings_control_and_snapshots() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SimulationSettings::default())
+        .init_resource::<SimControl>()
+        .insert_resource(BackendClientConfig::enabled(
+            "ws://localhost:8080/ws/ingest".to_string(),
+            "sim-commands".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+    app.world_mut().spawn((Ant, Transform::default()));
+    app.update();
+
+    let world = app.world_mut();
+    world.write_message(BackendCommand::SetSpeed { speed: 100.0 });
+    world.write_message(BackendCommand::Pause);
+    world.write_message(BackendCommand::RequestSnapshot);
+    app.update();
+
+    assert_eq!(
+        app.world()
+            .resource::<SimulationSettings>()
+            .speed_multiplier,
+        Config::MAX_SPEED_MULTIPLIER
+    );
+    assert!(app.world().resource::<SimControl>().is_paused());
+    assert_eq!(queued_messages_of_type(&app, "sim_hello").len(), 1);
+    assert_eq!(queued_messages_of_type(&app, "sim_food_snapshot").len(), 2);
+    let ant_snapshots = queued_messages_of_type(&app, "sim_ant_snapshot");
+    assert_eq!(ant_snapshots.len(), 2);
+    assert_eq!(
+        ant_snapshots[1]["payload"]["ants"].as_array().map(Vec::len),
+        Some(1)
+    );
+
+    let world = app.world_mut();
+    world.write_message(BackendCommand::Resume);
+    world.write_message(BackendCommand::Reset { seed: 11 });
+    app.update();
+
+    let control = app.world().resource::<SimControl>();
+    assert!(!control.is_paused());
+    assert_eq!(control.reset_requested(), Some(11));
+}
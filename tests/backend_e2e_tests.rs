@@ -4,9 +4,11 @@ use an_gatherers::collision::{initialize_hittables, update_hittable_positions};
 use an_gatherers::spatial_index::SpatialIndex;
 use an_gatherers::*;
 use axum::serve;
+use bevy::ecs::system::RunSystemOnce;
 use bevy::prelude::*;
-use gatherers_backend_rust::app::{AppState, build_router_with_state};
-use tokio::{net::TcpListener, task::JoinHandle, time::sleep};
+use gatherers_backend_rust::app::{AppState, CommandTarget, build_router_with_state};
+use gatherers_backend_rust::protocol::SimCommand;
+use tokio::{net::TcpListener, runtime::Runtime, task::JoinHandle, time::sleep};
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn bevy_client_plugin_sends_events_to_running_rust_backend() {
@@ -18,6 +20,7 @@ async fn bevy_client_plugin_sends_events_to_running_rust_backend() {
         .init_resource::<SpatialIndex>()
         .add_message::<HitEvent<Food, Ant>>()
         .insert_resource(SimulationSettings::default())
+        .init_resource::<SimRng>()
         .insert_resource(BackendClientConfig::enabled(
             server.ingest_ws_url(),
             "sim-e2e".to_string(),
@@ -86,6 +89,301 @@ async fn bevy_client_plugin_sends_events_to_running_rust_backend() {
     server.abort();
 }
 
+#[test]
+fn bevy_client_reconnects_and_resyncs_the_backend() {
+    let first_runtime = Runtime::new().expect("first server runtime");
+    let first_state = AppState::new();
+    let server = first_runtime.block_on(spawn_test_server(first_state.clone()));
+    let addr = server.addr;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(BackendClientConfig::enabled(
+            server.ingest_ws_url(),
+            "sim-reconnect".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+    let world = app.world_mut();
+    let ant = world.spawn((Ant, Transform::default())).id();
+    let carried = world
+        .spawn((Food, Transform::from_xyz(50.0, 0.0, 0.0)))
+        .id();
+    world.spawn((Food, Transform::from_xyz(-50.0, 0.0, 0.0)));
+
+    let synced = |state: &AppState, loose_food: usize| {
+        state.current_snapshot().sims.iter().any(|sim| {
+            sim.sim_id == "sim-reconnect"
+                && sim.ant_count == 1
+                && sim.loose_food_count == loose_food
+        })
+    };
+    assert!(
+        update_until(&mut app, |_| synced(&first_state, 2)),
+        "expected the first backend to see the sim"
+    );
+
+    // Pick one food up and spawn another, so the re-sync covers a carried
+    // slot and one past the startup set
+    app.world_mut()
+        .run_system_once(
+            move |mut writer: BackendEventWriter, mut commands: Commands| {
+                commands.entity(ant).add_child(carried);
+                writer.write(BackendSimEvent::FoodPickup {
+                    ant_id: writer.ant_id(ant),
+                    colony_id: 0,
+                    food_id: writer.food_id(carried),
+                    x: 50.0,
+                    y: 0.0,
+                    direction_x: 1.0,
+                    direction_y: 0.0,
+                    frame: 0,
+                });
+                let spawned = commands
+                    .spawn((Food, Transform::from_xyz(0.0, 50.0, 0.0)))
+                    .id();
+                let food_id = writer.spawned_food_id(spawned);
+                writer.write(BackendSimEvent::FoodSpawn {
+                    food_id,
+                    x: 0.0,
+                    y: 50.0,
+                });
+            },
+        )
+        .expect("edit system should run");
+    assert!(
+        update_until(&mut app, |_| synced(&first_state, 2)
+            && first_state.current_snapshot().sims[0].pickup_count == 1),
+        "expected the first backend to see the pickup and the spawn"
+    );
+
+    // Shutting the runtime down closes the listener and the open socket
+    first_runtime.shutdown_background();
+
+    let second_runtime = Runtime::new().expect("second server runtime");
+    let second_state = AppState::new();
+    let server = second_runtime.block_on(spawn_test_server_at(second_state.clone(), addr));
+
+    assert!(
+        update_until(&mut app, |_| synced(&second_state, 2)),
+        "expected the client to reconnect and re-send hello and snapshots; last_snapshot={:?}",
+        second_state.current_snapshot()
+    );
+
+    // The carried food's slot is known to the new backend, so a drop counts
+    app.world_mut()
+        .run_system_once(
+            move |mut writer: BackendEventWriter, mut commands: Commands| {
+                commands.entity(carried).remove::<ChildOf>();
+                writer.write(BackendSimEvent::FoodDrop {
+                    ant_id: writer.ant_id(ant),
+                    colony_id: 0,
+                    food_id: writer.food_id(carried),
+                    x: 20.0,
+                    y: 0.0,
+                    direction_x: 1.0,
+                    direction_y: 0.0,
+                    frame: 0,
+                });
+            },
+        )
+        .expect("drop system should run");
+    assert!(
+        update_until(&mut app, |_| synced(&second_state, 3)),
+        "expected the new backend to track the dropped food; last_snapshot={:?}",
+        second_state.current_snapshot()
+    );
+
+    second_runtime.block_on(async { server.abort() });
+}
+
+#[test]
+fn bevy_client_counts_events_lost_on_disconnect_as_drops() {
+    let runtime = Runtime::new().expect("server runtime");
+    let state = AppState::new();
+    let server = runtime.block_on(spawn_test_server(state.clone()));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(BackendClientConfig::enabled(
+            server.ingest_ws_url(),
+            "sim-drops".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin)
+        .add_systems(Update, |mut events: MessageWriter<BackendSimEvent>| {
+            events.write(BackendSimEvent::AntTurnMove {
+                ant_id: 0,
+                colony_id: 0,
+                x: 0.0,
+                y: 0.0,
+                direction_x: 1.0,
+                direction_y: 0.0,
+                frame: 0,
+            });
+        });
+    app.world_mut().spawn((Ant, Transform::default()));
+
+    assert!(
+        update_until(&mut app, |_| {
+            state
+                .current_snapshot()
+                .sims
+                .iter()
+                .any(|sim| sim.sim_id == "sim-drops" && sim.turn_move_count > 0)
+        }),
+        "expected the backend to see the sim's events"
+    );
+    assert_eq!(app.world().resource::<BackendDroppedEvents>().0, 0);
+
+    // The turn move queued in the frame the close is noticed never goes out
+    runtime.shutdown_background();
+    assert!(
+        update_until(&mut app, |app| app
+            .world()
+            .resource::<BackendDroppedEvents>()
+            .0
+            > 0),
+        "expected events cleared on disconnect to count as drops"
+    );
+}
+
+#[test]
+fn bevy_client_applies_commands_sent_by_the_backend() {
+    let runtime = Runtime::new().expect("server runtime");
+    let state = AppState::new();
+    let server = runtime.block_on(spawn_test_server(state.clone()));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SimulationSettings::default())
+        .init_resource::<SimControl>()
+        .insert_resource(BackendClientConfig::enabled(
+            server.ingest_ws_url(),
+            "sim-commands".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+    app.world_mut().spawn((Ant, Transform::default()));
+
+    assert!(
+        update_until(&mut app, |_| {
+            state
+                .current_snapshot()
+                .sims
+                .iter()
+                .any(|sim| sim.sim_id == "sim-commands")
+        }),
+        "expected the backend to see the sim"
+    );
+
+    let target = CommandTarget::Prefix("sim-comm".to_string());
+    for command in [SimCommand::SetSpeed { speed: 2.5 }, SimCommand::Pause] {
+        assert_eq!(state.send_command(&target, &command), ["sim-commands"]);
+    }
+
+    assert!(
+        update_until(&mut app, |app| {
+            let world = app.world();
+            let speed = world.resource::<SimulationSettings>().speed_multiplier;
+            world.resource::<SimControl>().is_paused() && speed == 2.5
+        }),
+        "expected the sim to apply set_speed and pause"
+    );
+
+    runtime.block_on(async { server.abort() });
+}
+
+#[test]
+fn bevy_client_answers_request_snapshot_while_food_is_carried() {
+    let runtime = Runtime::new().expect("server runtime");
+    let state = AppState::new();
+    let server = runtime.block_on(spawn_test_server(state.clone()));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(BackendClientConfig::enabled(
+            server.ingest_ws_url(),
+            "sim-request-snapshot".to_string(),
+        ))
+        .add_plugins(BackendClientPlugin);
+    let world = app.world_mut();
+    let ant = world.spawn((Ant, Transform::default())).id();
+    let carried = world.spawn((Food, Transform::default())).id();
+    world.entity_mut(ant).add_child(carried);
+    let picked = world
+        .spawn((Food, Transform::from_xyz(50.0, 0.0, 0.0)))
+        .id();
+    let vanished = world
+        .spawn((Food, Transform::from_xyz(-50.0, 0.0, 0.0)))
+        .id();
+
+    let sim = |state: &AppState| {
+        state
+            .current_snapshot()
+            .sims
+            .into_iter()
+            .find(|sim| sim.sim_id == "sim-request-snapshot")
+    };
+    assert!(
+        update_until(&mut app, |_| sim(&state)
+            .is_some_and(|sim| sim.loose_food_count == 2)),
+        "expected the backend to see the two loose foods"
+    );
+
+    app.world_mut()
+        .run_system_once(
+            move |mut writer: BackendEventWriter, mut commands: Commands| {
+                commands.entity(ant).add_child(picked);
+                writer.write(BackendSimEvent::FoodPickup {
+                    ant_id: writer.ant_id(ant),
+                    colony_id: 0,
+                    food_id: writer.food_id(picked),
+                    x: 50.0,
+                    y: 0.0,
+                    direction_x: 1.0,
+                    direction_y: 0.0,
+                    frame: 0,
+                });
+            },
+        )
+        .expect("pickup system should run");
+    assert!(
+        update_until(&mut app, |_| {
+            sim(&state).is_some_and(|sim| sim.pickup_count == 1 && sim.loose_food_count == 1)
+        }),
+        "expected the backend to see the pickup"
+    );
+
+    // Only a fresh snapshot tells the backend this food is gone
+    app.world_mut().despawn(vanished);
+    let target = CommandTarget::Sim("sim-request-snapshot".to_string());
+    assert_eq!(
+        state.send_command(&target, &SimCommand::RequestSnapshot),
+        ["sim-request-snapshot"]
+    );
+
+    // The pickup count survives, so the snapshot landed on the live session
+    assert!(
+        update_until(&mut app, |_| {
+            sim(&state).is_some_and(|sim| sim.pickup_count == 1 && sim.loose_food_count == 0)
+        }),
+        "expected the re-sent snapshot to be applied; last_snapshot={:?}",
+        state.current_snapshot()
+    );
+
+    runtime.block_on(async { server.abort() });
+}
+
+/// Updates the app in real time until `done` holds, for at most 5 seconds.
+fn update_until(app: &mut App, done: impl Fn(&App) -> bool) -> bool {
+    for _ in 0..250 {
+        app.update();
+        std::thread::sleep(Duration::from_millis(20));
+        if done(app) {
+            return true;
+        }
+    }
+    false
+}
+
 struct TestServer {
     addr: SocketAddr,
     task: JoinHandle<()>,
@@ -102,10 +400,23 @@ impl TestServer {
 }
 
 async fn spawn_test_server(state: AppState) -> TestServer {
+    spawn_test_server_at(state, SocketAddr::from(([127, 0, 0, 1], 0))).await
+}
+
+async fn spawn_test_server_at(state: AppState, addr: SocketAddr) -> TestServer {
     let router = build_router_with_state(state);
-    let listener = TcpListener::bind("127.0.0.1:0")
-        .await
-        .expect("test listener should bind");
+    // A runtime shut down in the background may still hold a reused port
+    let mut attempts = 0;
+    let listener = loop {
+        match TcpListener::bind(addr).await {
+            Ok(listener) => break listener,
+            Err(_) if attempts < 50 => {
+                attempts += 1;
+                sleep(Duration::from_millis(20)).await;
+            }
+            Err(err) => panic!("test listener should bind: {err}"),
+        }
+    };
     let addr = listener.local_addr().expect("listener addr");
     let task = tokio::spawn(async move {
         serve(listener, router)
@@ -0,0 +1,108 @@
+use an_gatherers::experiment::{BatchConfig, ExperimentRun, ParameterSweep, run_headless};
+use an_gatherers::{ClusteringSummary, Config, PickupRule, SimulationSettings, clustering_summary};
+use bevy::prelude::Vec2;
+
+#[test]
+fn clustering_summary_counts_cells_and_nearest_neighbours() {
+    let positions = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(3.0, 4.0),
+        Vec2::new(100.0, 0.0),
+    ];
+
+    let summary = clustering_summary(&positions, 50.0);
+
+    assert_eq!(summary.loose_food_count, 3);
+    assert_eq!(summary.occupied_cell_count, 2);
+    // Nearest neighbours: 5, 5 and sqrt(97^2 + 4^2)
+    let expected = (5.0 + 5.0 + (97.0f64 * 97.0 + 16.0).sqrt()) / 3.0;
+    assert!((summary.nearest_neighbor_mean_distance - expected).abs() < 1e-9);
+
+    let empty = clustering_summary(&[], 50.0);
+    assert_eq!(empty, ClusteringSummary::default());
+}
+
+#[test]
+fn batch_args_build_the_seed_and_parameter_cross_product() {
+    let config = BatchConfig::from_args([
+        "--seeds",
+        "1-3,10",
+        "--ticks",
+        "120",
+        "--sweep",
+        "pickup_rule=always,deneubourg",
+        "--sweep",
+        "food_count=40,80,120",
+    ])
+    .expect("batch args should parse");
+
+    assert_eq!(config.seeds, vec![1, 2, 3, 10]);
+    assert_eq!(config.ticks, 120);
+    assert_eq!(config.combinations().len(), 6);
+    assert_eq!(
+        config.csv_header(),
+        "seed,pickup_rule,food_count,ticks,loose_food_count,occupied_cell_count,nearest_neighbor_mean_distance"
+    );
+
+    let last = config.combinations().pop().unwrap();
+    let settings = config.settings_for(&last).unwrap();
+    assert_eq!(settings.pickup_rule, PickupRule::Deneubourg);
+    assert_eq!(settings.food_count, 120);
+}
+
+#[test]
+fn batch_args_reject_bad_sweeps() {
+    for (args, expected) in [
+        (
+            vec!["--sweep", "food_count=many"],
+            "food_count must be a valid u32",
+        ),
+        (vec!["--sweep", "speed_of_light=1"], "unknown parameter"),
+        (
+            vec!["--sweep", "pickup_rule=sometimes"],
+            "pickup_rule must be",
+        ),
+        (
+            vec!["--sweep", "collision_radius=5,20"],
+            "spatial_cell_size",
+        ),
+        (
+            vec!["--sweep", "k1=0.1,0"],
+            "deneubourg_k1 must be positive",
+        ),
+        (vec!["--seeds", "5-1"], "--seeds range"),
+        (vec!["--ticks"], "--ticks requires a value"),
+        (
+            vec!["--world-size", "1280x0"],
+            "--world-size must be at least 2",
+        ),
+    ] {
+        let error = BatchConfig::from_args(args.clone()).expect_err("bad args should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected error for {args:?} to contain {expected:?}, got {error}"
+        );
+    }
+
+    assert!(ParameterSweep::parse("food_count=").is_err());
+}
+
+#[test]
+fn headless_run_is_reproducible_for_a_seed() {
+    let run = ExperimentRun {
+        seed: 11,
+        ticks: 300,
+        world_size: Config::DEFAULT_WORLD_SIZE,
+        settings: SimulationSettings::default(),
+    };
+
+    let first = run_headless(&run);
+    let second = run_headless(&run);
+
+    assert_eq!(
+        first, second,
+        "same seed and tick budget should reproduce the metrics"
+    );
+    assert!(first.loose_food_count <= Config::FOOD_COUNT as usize);
+    assert!(first.occupied_cell_count > 0);
+}
@@ -1,5 +1,14 @@
-use an_gatherers::{RuntimeConfig, SimulationSettings, generate_spawn_layout};
-use bevy::prelude::Vec2;
+use an_gatherers::{
+    Ant, BackendDropPolicy, Colony, ColonyId, Config, Food, PickupRule, RuntimeConfig, Scenario,
+    SimulationSettings, generate_spawn_layout, spawn_world,
+};
+use bevy::prelude::{Transform, Vec2, World};
+
+fn write_scenario(name: &str, contents: &str) -> String {
+    let path = std::env::temp_dir().join(format!("gatherers-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).expect("scenario file should be writable");
+    path.to_string_lossy().into_owned()
+}
 
 #[test]
 fn parses_explicit_runtime_env_values() {
@@ -55,3 +64,421 @@ fn different_seeds_change_spawn_layout() {
         "different configured seeds should not produce the same initial sim layout"
     );
 }
+
+#[test]
+fn headless_mode_uses_window_size_or_default_world_size() {
+    let config = RuntimeConfig::from_pairs([("GATHERERS_HEADLESS", "1")])
+        .expect("runtime config should parse");
+    assert!(config.headless);
+    assert_eq!(config.resolved_world_size(), Config::DEFAULT_WORLD_SIZE);
+
+    let config = RuntimeConfig::from_pairs([
+        ("GATHERERS_HEADLESS", "true"),
+        ("GATHERERS_WINDOW_WIDTH", "800"),
+        ("GATHERERS_WINDOW_HEIGHT", "600"),
+    ])
+    .expect("runtime config should parse");
+    assert_eq!(config.resolved_world_size(), Vec2::new(800.0, 600.0));
+
+    let error = RuntimeConfig::from_pairs([("GATHERERS_HEADLESS", "yes")])
+        .expect_err("invalid headless flag should be rejected");
+    assert!(
+        error.contains("GATHERERS_HEADLESS"),
+        "expected error to name the variable, got {error}"
+    );
+}
+
+#[test]
+fn explicit_world_size_overrides_window_size() {
+    let config = RuntimeConfig::from_pairs([
+        ("GATHERERS_WINDOW_WIDTH", "800"),
+        ("GATHERERS_WINDOW_HEIGHT", "600"),
+        ("GATHERERS_WORLD_WIDTH", "2560"),
+        ("GATHERERS_WORLD_HEIGHT", "1440"),
+    ])
+    .expect("runtime config should parse");
+    assert_eq!(config.world_size, Some((2560, 1440)));
+    assert_eq!(config.resolved_world_size(), Vec2::new(2560.0, 1440.0));
+
+    let error = RuntimeConfig::from_pairs([("GATHERERS_WORLD_WIDTH", "2560")])
+        .expect_err("world width without height should be rejected");
+    assert!(
+        error.contains("GATHERERS_WORLD_HEIGHT"),
+        "expected error to name the missing variable, got {error}"
+    );
+
+    for pairs in [
+        [
+            ("GATHERERS_WORLD_WIDTH", "1"),
+            ("GATHERERS_WORLD_HEIGHT", "600"),
+        ],
+        [
+            ("GATHERERS_WINDOW_WIDTH", "800"),
+            ("GATHERERS_WINDOW_HEIGHT", "0"),
+        ],
+    ] {
+        let error = RuntimeConfig::from_pairs(pairs)
+            .expect_err("degenerate world sizes should be rejected");
+        assert!(
+            error.contains("must be at least 2"),
+            "expected error for {pairs:?} to name the minimum, got {error}"
+        );
+    }
+}
+
+#[test]
+fn parses_colony_settings() {
+    let config = RuntimeConfig::from_pairs([
+        ("GATHERERS_COLONIES", "3"),
+        ("GATHERERS_RETURN_TO_NEST", "true"),
+    ])
+    .expect("runtime config should parse");
+    assert_eq!(config.colony_count, 3);
+    assert!(config.return_to_nest);
+
+    let config = RuntimeConfig::default();
+    assert_eq!(config.colony_count, 1);
+    assert!(!config.return_to_nest);
+
+    let error = RuntimeConfig::from_pairs([("GATHERERS_COLONIES", "0")])
+        .expect_err("zero colonies should be rejected");
+    assert!(
+        error.contains("GATHERERS_COLONIES"),
+        "expected error to name the variable, got {error}"
+    );
+}
+
+#[test]
+fn parses_backend_heartbeat_interval() {
+    let config = RuntimeConfig::from_pairs([("GATHERERS_BACKEND_HEARTBEAT_SECS", "1.5")])
+        .expect("runtime config should parse");
+    assert_eq!(config.backend_heartbeat_secs, 1.5);
+    assert_eq!(
+        RuntimeConfig::default().backend_heartbeat_secs,
+        Config::BACKEND_HEARTBEAT_SECS
+    );
+
+    let error = RuntimeConfig::from_pairs([("GATHERERS_BACKEND_HEARTBEAT_SECS", "0")])
+        .expect_err("a zero heartbeat interval should be rejected");
+    assert!(
+        error.contains("GATHERERS_BACKEND_HEARTBEAT_SECS"),
+        "expected error to name the variable, got {error}"
+    );
+}
+
+#[test]
+fn parses_backend_queue_capacity_and_drop_policy() {
+    let config = RuntimeConfig::from_pairs([
+        ("GATHERERS_BACKEND_QUEUE_CAPACITY", "64"),
+        ("GATHERERS_BACKEND_DROP_POLICY", "oldest"),
+    ])
+    .expect("runtime config should parse");
+    assert_eq!(config.backend_queue_capacity, 64);
+    assert_eq!(config.backend_drop_policy, BackendDropPolicy::DropOldest);
+
+    let defaults = RuntimeConfig::default();
+    assert_eq!(
+        defaults.backend_queue_capacity,
+        Config::BACKEND_OUTBOUND_CAPACITY
+    );
+    assert_eq!(defaults.backend_drop_policy, BackendDropPolicy::DropNewest);
+
+    for (key, value) in [
+        ("GATHERERS_BACKEND_QUEUE_CAPACITY", "0"),
+        ("GATHERERS_BACKEND_DROP_POLICY", "random"),
+    ] {
+        let error = RuntimeConfig::from_pairs([(key, value)])
+            .expect_err("an invalid queue setting should be rejected");
+        assert!(
+            error.contains(key),
+            "expected error to name the variable, got {error}"
+        );
+    }
+}
+
+#[test]
+fn spawn_layout_places_ants_around_their_colony_nest() {
+    let settings = SimulationSettings {
+        colony_count: 3,
+        ..Default::default()
+    };
+    let map_size = Vec2::new(1280.0, 720.0);
+
+    let layout = generate_spawn_layout(map_size, &settings, Some(5));
+
+    assert_eq!(layout.nests.len(), 3);
+    for nest in &layout.nests {
+        let members: Vec<_> = layout
+            .ants
+            .iter()
+            .filter(|ant| ant.colony_id == nest.colony_id)
+            .collect();
+        assert!(!members.is_empty(), "colony {} has no ants", nest.colony_id);
+        for ant in members {
+            let distance = ant.position.truncate().distance(nest.position);
+            assert!(
+                distance <= Config::NEST_SPAWN_RADIUS + 1e-3,
+                "ant spawned {distance} away from its nest"
+            );
+        }
+    }
+}
+
+#[test]
+fn spawn_world_spawns_the_seeded_layout() {
+    let settings = SimulationSettings {
+        colony_count: 2,
+        food_count: 7,
+        ..Default::default()
+    };
+    let map_size = Vec2::new(400.0, 300.0);
+    let layout = generate_spawn_layout(map_size, &settings, Some(9));
+
+    let mut world = World::new();
+    spawn_world(&mut world.commands(), map_size, &settings, Some(9));
+    world.flush();
+
+    let nests: Vec<u32> = world
+        .query::<&Colony>()
+        .iter(&world)
+        .map(|colony| colony.id)
+        .collect();
+    assert_eq!(nests.len(), layout.nests.len());
+    let mut ants: Vec<(u32, Vec2)> = world
+        .query::<(&ColonyId, &Transform, &Ant)>()
+        .iter(&world)
+        .map(|(colony, transform, _)| (colony.0, transform.translation.truncate()))
+        .collect();
+    let mut expected: Vec<(u32, Vec2)> = layout
+        .ants
+        .iter()
+        .map(|ant| (ant.colony_id, ant.position.truncate()))
+        .collect();
+    ants.sort_by(|a, b| a.1.x.total_cmp(&b.1.x));
+    expected.sort_by(|a, b| a.1.x.total_cmp(&b.1.x));
+    assert_eq!(ants, expected);
+    assert_eq!(
+        world.query::<&Food>().iter(&world).count(),
+        layout.food_positions.len()
+    );
+}
+
+#[test]
+fn loads_toml_scenario_into_settings_and_layout() {
+    let path = write_scenario(
+        "scenario.toml",
+        r#"
+food_count = 12
+ant_spawn_step = 100.0
+turn_angle_range = 0.5
+pickup_cooldown = 0.25
+collision_radius = 6.0
+spatial_cell_size = 15.0
+"#,
+    );
+    let config = RuntimeConfig::from_pairs([("GATHERERS_SCENARIO", path.as_str())])
+        .expect("runtime config should parse");
+
+    let settings = config.simulation_settings();
+    assert_eq!(settings.food_count, 12);
+    assert_eq!(settings.ant_spawn_step, 100.0);
+    assert_eq!(settings.turn_angle_range, 0.5);
+    assert_eq!(settings.pickup_cooldown, 0.25);
+    assert_eq!(settings.collision_radius(), 6.0);
+    assert_eq!(settings.spatial_cell_size, 15.0);
+
+    let layout = generate_spawn_layout(Vec2::new(1000.0, 500.0), &settings, Some(1));
+    assert_eq!(layout.food_positions.len(), 12);
+    assert_eq!(layout.ants.len(), 10);
+}
+
+#[test]
+fn loads_json_scenario_and_keeps_defaults_for_missing_keys() {
+    let path = write_scenario("scenario.json", r#"{ "food_count": 5 }"#);
+    let config = RuntimeConfig::from_pairs([("GATHERERS_SCENARIO", path.as_str())])
+        .expect("runtime config should parse");
+
+    let settings = config.simulation_settings();
+    assert_eq!(settings.food_count, 5);
+    assert_eq!(settings.turn_angle_range, Config::TURN_ANGLE_RANGE);
+    assert_eq!(settings.collision_radius(), Config::BASE_COLLISION_RADIUS);
+}
+
+#[test]
+fn pheromones_are_off_by_default_and_enabled_by_scenario() {
+    assert_eq!(SimulationSettings::default().pheromone_deposit_rate, 0.0);
+
+    let path = write_scenario(
+        "pheromones.toml",
+        r#"
+pheromone_deposit_rate = 2.0
+pheromone_evaporation_rate = 0.2
+pheromone_diffusion_rate = 0.0
+pheromone_steering_rate = 3.0
+"#,
+    );
+    let config = RuntimeConfig::from_pairs([("GATHERERS_SCENARIO", path.as_str())])
+        .expect("runtime config should parse");
+
+    let settings = config.simulation_settings();
+    assert_eq!(settings.pheromone_deposit_rate, 2.0);
+    assert_eq!(settings.pheromone_evaporation_rate, 0.2);
+    assert_eq!(settings.pheromone_diffusion_rate, 0.0);
+    assert_eq!(settings.pheromone_steering_rate, 3.0);
+}
+
+#[test]
+fn scenario_selects_the_pickup_rule_and_constants() {
+    let scenario = Scenario::from_toml_str(
+        r#"
+pickup_rule = "deneubourg"
+k1 = 0.2
+deneubourg_k2 = 0.5
+"#,
+    )
+    .expect("scenario should parse");
+    let mut settings = SimulationSettings::default();
+    scenario.apply(&mut settings);
+
+    assert_eq!(settings.pickup_rule, PickupRule::Deneubourg);
+    assert_eq!(settings.deneubourg_k1, 0.2);
+    assert_eq!(settings.deneubourg_k2, 0.5);
+}
+
+#[test]
+fn rejects_invalid_scenario_values() {
+    for (contents, expected) in [
+        ("ant_spawn_step = 0.0", "ant_spawn_step"),
+        ("turn_angle_range = 4.0", "turn_angle_range"),
+        ("pickup_cooldown = -1.0", "pickup_cooldown"),
+        ("pheromone_deposit_rate = -0.5", "pheromone_deposit_rate"),
+        (
+            "pheromone_evaporation_rate = nan",
+            "pheromone_evaporation_rate",
+        ),
+        ("collision_radius = 15.0", "spatial_cell_size"),
+        ("food_count = -3", "food_count"),
+        ("food_cnt = 3", "food_cnt"),
+        ("pickup_rule = \"sometimes\"", "pickup_rule"),
+        ("deneubourg_k1 = 0.0", "deneubourg_k1"),
+        ("k2 = -1.0", "deneubourg_k2"),
+    ] {
+        let error =
+            Scenario::from_toml_str(contents).expect_err("invalid scenario should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected error for {contents:?} to mention {expected}, got {error}"
+        );
+    }
+
+    let path = write_scenario("broken.toml", "turn_angle_range = 4.0");
+    let error = RuntimeConfig::from_pairs([("GATHERERS_SCENARIO", path.as_str())])
+        .expect_err("invalid scenario should be rejected");
+    assert!(
+        error.contains("broken.toml"),
+        "expected error to name the file, got {error}"
+    );
+
+    let error = RuntimeConfig::from_pairs([("GATHERERS_SCENARIO", "scenario.yaml")])
+        .expect_err("unknown scenario format should be rejected");
+    assert!(error.contains("scenario.yaml"), "got {error}");
+}
+
+#[test]
+fn cli_flags_override_env_values() {
+    let mut config = RuntimeConfig::from_pairs([
+        ("GATHERERS_SIM_SEED", "1"),
+        ("GATHERERS_STARTUP_SPEED", "2.0"),
+        ("GATHERERS_SIM_ID", "from-env"),
+        ("GATHERERS_WINDOW_WIDTH", "640"),
+        ("GATHERERS_WINDOW_HEIGHT", "480"),
+    ])
+    .expect("runtime config should parse");
+
+    let scenario = write_scenario("cli.toml", "pickup_rule = \"deneubourg\"");
+    config
+        .apply_args([
+            "--seed",
+            "99",
+            "--speed=7.5",
+            "--backend-url",
+            "ws://127.0.0.1:9/ws/ingest",
+            "--window-size",
+            "800x600",
+            "--headless",
+            "--ticks",
+            "1000",
+            "--scenario",
+            scenario.as_str(),
+        ])
+        .expect("flags should parse");
+
+    assert_eq!(config.seed, Some(99));
+    assert_eq!(config.startup_speed, 7.5);
+    assert_eq!(
+        config.backend_ws_url.as_deref(),
+        Some("ws://127.0.0.1:9/ws/ingest")
+    );
+    assert_eq!(config.sim_id, "from-env", "unset flags keep the env value");
+    assert_eq!(config.window_size, Some((800, 600)));
+    assert!(config.headless);
+    assert_eq!(config.max_ticks, Some(1000));
+    assert_eq!(
+        config.simulation_settings().pickup_rule,
+        PickupRule::Deneubourg
+    );
+}
+
+#[test]
+fn cli_flags_report_bad_values() {
+    for (args, expected) in [
+        (vec!["--seed", "abc"], "--seed must be a valid u64"),
+        (vec!["--speed"], "--speed requires a value"),
+        (
+            vec!["--window-size", "800"],
+            "--window-size must be WIDTHxHEIGHT",
+        ),
+        (
+            vec!["--window-size", "800xtall"],
+            "--window-size must be a valid u32",
+        ),
+        (
+            vec!["--window-size", "1x720"],
+            "--window-size must be at least 2",
+        ),
+        (vec!["--bogus"], "unknown argument"),
+    ] {
+        let mut config = RuntimeConfig::default();
+        let error = config
+            .apply_args(args.clone())
+            .expect_err("bad flags should be rejected");
+        assert!(
+            error.contains(expected),
+            "expected error for {args:?} to contain {expected:?}, got {error}"
+        );
+    }
+}
+
+#[test]
+fn help_flag_is_detected() {
+    let switches = ["--headless"];
+    assert!(an_gatherers::runtime::help_requested(
+        ["--seed", "1", "--help"],
+        &switches
+    ));
+    assert!(an_gatherers::runtime::help_requested(["-h"], &switches));
+    assert!(an_gatherers::runtime::help_requested(
+        ["--headless", "--help"],
+        &switches
+    ));
+    assert!(!an_gatherers::runtime::help_requested(
+        ["--headless"],
+        &switches
+    ));
+    // A flag's value is never read as a request for help
+    assert!(!an_gatherers::runtime::help_requested(
+        ["--scenario", "--help"],
+        &switches
+    ));
+    assert!(an_gatherers::runtime::USAGE.contains("--ticks"));
+}
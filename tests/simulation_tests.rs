@@ -123,6 +123,7 @@ fn create_test_app_with_dt(frame_ms: u64) -> App {
         .add_message::<HitEvent<Food, Ant>>()
         .add_message::<BackendSimEvent>()
         .insert_resource(SimulationSettings::default())
+        .init_resource::<SimRng>()
         .insert_resource(TestEventCollector::default())
         .insert_resource(Time::<()>::default())
         .insert_resource(FrameCount::default())
@@ -465,24 +466,23 @@ fn test_cooldown_duration_consistency_across_speeds() {
 
 // --- Regression tests: speed scaling ---
 
-fn create_movement_test_app_with_dt(speed_multiplier: f32, frame_ms: u64) -> App {
+/// Runs the fixed-timestep pipeline with `frame_ms` real milliseconds per
+/// frame; the speed multiplier decides how many ticks fit into each frame.
+fn create_fixed_pipeline_app_with_dt(speed_multiplier: f32, frame_ms: u64) -> App {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins)
         .insert_resource(SimulationSettings {
             speed_multiplier,
+            ..default()
         })
-        .insert_resource(Time::<()>::default())
         .insert_resource(TimeUpdateStrategy::ManualDuration(
             std::time::Duration::from_millis(frame_ms),
         ))
-        .add_systems(Update, gatherer_movement);
+        .add_plugins(SimulationPlugin)
+        .add_plugins(CollisionPlugin::<Food, Ant>::new());
     app
 }
 
-fn create_movement_test_app(speed_multiplier: f32) -> App {
-    create_movement_test_app_with_dt(speed_multiplier, DEFAULT_FRAME_MS)
-}
-
 fn spawn_rightward_ant(app: &mut App) -> Entity {
     app.world_mut()
         .spawn((
@@ -500,19 +500,25 @@ fn measure_displacement(speed_multiplier: f32) -> f32 {
 }
 
 fn measure_displacement_with_dt(speed_multiplier: f32, frame_ms: u64) -> f32 {
-    let mut app = create_movement_test_app_with_dt(speed_multiplier, frame_ms);
+    measure_displacement_and_ticks(speed_multiplier, frame_ms).0
+}
+
+fn measure_displacement_and_ticks(speed_multiplier: f32, frame_ms: u64) -> (f32, u64) {
+    let mut app = create_fixed_pipeline_app_with_dt(speed_multiplier, frame_ms);
     let ant = spawn_rightward_ant(&mut app);
 
     for _ in 0..MOVEMENT_TEST_FRAMES {
         app.update();
     }
 
-    app.world()
+    let displacement = app
+        .world()
         .entity(ant)
         .get::<Transform>()
         .unwrap()
         .translation
-        .x
+        .x;
+    (displacement, app.world().resource::<SimTick>().0)
 }
 
 #[test]
@@ -577,118 +583,81 @@ fn test_unlimited_at_least_as_fast_as_max_normal_all_frame_rates() {
 }
 
 #[test]
-fn test_effective_speed_unlimited_adapts_to_frame_time() {
-    let settings = SimulationSettings {
-        speed_multiplier: Config::UNLIMITED_SPEED,
-    };
-    let safe_step = settings.safe_step_distance();
-
-    for &dt_ms in &[8u64, 16, 33, 50] {
-        let dt = dt_ms as f32 / 1000.0;
-        let effective = settings.effective_speed(dt);
-        let expected = safe_step / dt;
-
-        println!(
-            "At {}ms: effective_speed = {:.1}, expected = {:.1}",
-            dt_ms, effective, expected
-        );
-
-        assert!(
-            (effective - expected).abs() < 0.01,
-            "At {}ms, effective_speed should be {:.1}, got {:.1}",
-            dt_ms,
-            expected,
-            effective
-        );
-    }
-}
-
-#[test]
-fn test_effective_speed_normal_unaffected_at_high_fps() {
-    let settings = SimulationSettings {
-        speed_multiplier: 5.0,
-    };
-    let dt = 0.016;
-    let effective = settings.effective_speed(dt);
-    let nominal = Config::BASE_ANT_SPEED * 5.0;
+fn test_tick_step_is_below_safe_step() {
+    let (disp, ticks) = measure_displacement_and_ticks(Config::UNLIMITED_SPEED, DEFAULT_FRAME_MS);
+    let per_tick = disp / ticks as f32;
+    let safe_step = SimulationSettings::default().safe_step_distance();
 
     assert!(
-        (effective - nominal).abs() < 0.01,
-        "At 60fps, 5x should not be capped: expected {}, got {}",
-        nominal,
-        effective
+        per_tick < safe_step,
+        "one tick ({:.2}) must move less than the safe step ({:.2})",
+        per_tick,
+        safe_step
     );
 }
 
 #[test]
-fn test_effective_multiplier_unlimited() {
-    let settings = SimulationSettings {
-        speed_multiplier: Config::UNLIMITED_SPEED,
-    };
+fn test_higher_multiplier_runs_proportionally_more_ticks() {
+    let (_, ticks_1x) = measure_displacement_and_ticks(1.0, DEFAULT_FRAME_MS);
+    let (_, ticks_10x) = measure_displacement_and_ticks(10.0, DEFAULT_FRAME_MS);
 
-    let mult_60 = settings.effective_multiplier(0.016);
-    println!("At 60fps: effective multiplier = {:.2}x", mult_60);
-    assert!(
-        mult_60 > 10.0,
-        "Unlimited at 60fps should exceed 10x: got {:.1}x",
-        mult_60
-    );
+    println!("1x: {} ticks, 10x: {} ticks", ticks_1x, ticks_10x);
 
-    let mult_30 = settings.effective_multiplier(0.033);
-    println!("At 30fps: effective multiplier = {:.2}x", mult_30);
+    assert!(ticks_1x > 0);
+    let ratio = ticks_10x as f32 / ticks_1x as f32;
     assert!(
-        mult_30 > 5.0 && mult_30 < 6.0,
-        "Unlimited at 30fps should be ~5.5x: got {:.1}x",
-        mult_30
+        (9.0..=11.5).contains(&ratio),
+        "10x should run about ten times the ticks of 1x, got {:.2}x",
+        ratio
     );
 }
 
 #[test]
-fn test_unlimited_displacement_equals_safe_step_per_frame() {
-    let safe_step = SimulationSettings::default().safe_step_distance();
-
-    for &dt_ms in &[8u64, 16, 33, 50] {
-        let disp = measure_displacement_with_dt(Config::UNLIMITED_SPEED, dt_ms);
-        let non_zero_frames = (MOVEMENT_TEST_FRAMES - 1) as f32;
-        let per_frame = disp / non_zero_frames;
-
-        println!(
-            "At {}ms: per-frame displacement = {:.2}, safe_step = {:.2}",
-            dt_ms, per_frame, safe_step
-        );
-
-        assert!(
-            (per_frame - safe_step).abs() < 0.5,
-            "At {}ms, unlimited should move {:.1} per frame, got {:.1}",
-            dt_ms,
-            safe_step,
-            per_frame
-        );
+fn test_per_tick_displacement_is_independent_of_speed_and_frame_rate() {
+    // One fixed tick at base speed, whatever the multiplier
+    let tick_step = Config::BASE_ANT_SPEED / Config::SIM_TICK_HZ as f32;
+
+    for &speed in &[1.0, Config::MAX_SPEED_MULTIPLIER, Config::UNLIMITED_SPEED] {
+        for &dt_ms in &[8u64, 16, 33, 50] {
+            let (disp, ticks) = measure_displacement_and_ticks(speed, dt_ms);
+            let per_tick = disp / ticks as f32;
+
+            assert!(
+                (per_tick - tick_step).abs() < 0.01,
+                "At speed {} and {}ms frames, ants should move {:.2} per tick, got {:.2}",
+                speed,
+                dt_ms,
+                tick_step,
+                per_tick
+            );
+        }
     }
 }
 
 #[test]
-fn test_effective_speed_zero_delta_returns_zero() {
-    let settings = SimulationSettings {
-        speed_multiplier: Config::UNLIMITED_SPEED,
-    };
-    assert_eq!(settings.effective_speed(0.0), 0.0);
-    assert_eq!(settings.effective_multiplier(0.0), 0.0);
-}
+fn test_achieved_tick_rate_reports_real_ticks_per_second() {
+    let mut app = create_fixed_pipeline_app_with_dt(2.0, DEFAULT_FRAME_MS);
 
-// --- Regression test: unlimited speed must not tunnel through food ---
+    for _ in 0..120 {
+        app.update();
+    }
 
-#[test]
-fn test_unlimited_speed_no_tunneling() {
-    let mut app = create_test_app();
+    let achieved = app
+        .world()
+        .resource::<AchievedTickRate>()
+        .ticks_per_second();
+    let nominal = Config::SIM_TICK_HZ as f32 * 2.0;
+    assert!(
+        (achieved - nominal).abs() < nominal * 0.1,
+        "expected about {:.0} ticks/s at 2x, measured {:.1}",
+        nominal,
+        achieved
+    );
+}
 
-    {
-        let world = app.world_mut();
-        world.insert_resource(SimulationSettings {
-            speed_multiplier: Config::UNLIMITED_SPEED,
-        });
-    }
+// --- Regression test: unlimited speed must not tunnel through food ---
 
+fn spawn_ant_heading_at_food(app: &mut App) -> Entity {
     let world = app.world_mut();
 
     let ant = world
@@ -701,18 +670,20 @@ fn test_unlimited_speed_no_tunneling() {
         ))
         .id();
 
-    let food = world
-        .spawn((
-            Food,
-            Transform::from_translation(Vec3::new(50.0, 0.0, Config::FOOD_Z_LAYER)),
-            Bounding::from_radius(5.0),
-            Collidable,
-        ))
-        .id();
+    world.spawn((
+        Food,
+        Transform::from_translation(Vec3::new(50.0, 0.0, Config::FOOD_Z_LAYER)),
+        Bounding::from_radius(5.0),
+        Collidable,
+    ));
 
-    let mut spatial_index = world.resource_mut::<SpatialIndex>();
-    spatial_index.update(food, Vec2::new(50.0, 0.0));
-    drop(spatial_index);
+    ant
+}
+
+#[test]
+fn test_unlimited_speed_no_tunneling() {
+    let mut app = create_fixed_pipeline_app_with_dt(Config::UNLIMITED_SPEED, DEFAULT_FRAME_MS);
+    let ant = spawn_ant_heading_at_food(&mut app);
 
     for _ in 0..100 {
         app.update();
@@ -746,39 +717,8 @@ fn test_unlimited_speed_no_tunneling() {
 
 #[test]
 fn test_unlimited_speed_no_tunneling_at_low_fps() {
-    let mut app = create_test_app_with_dt(50);
-
-    {
-        let world = app.world_mut();
-        world.insert_resource(SimulationSettings {
-            speed_multiplier: Config::UNLIMITED_SPEED,
-        });
-    }
-
-    let world = app.world_mut();
-
-    let ant = world
-        .spawn((
-            Ant,
-            Velocity(Vec2::new(1.0, 0.0)),
-            Transform::from_translation(Vec3::new(-50.0, 0.0, Config::ANT_Z_LAYER)),
-            Bounding::from_radius(10.0),
-            Collidable,
-        ))
-        .id();
-
-    let food = world
-        .spawn((
-            Food,
-            Transform::from_translation(Vec3::new(50.0, 0.0, Config::FOOD_Z_LAYER)),
-            Bounding::from_radius(5.0),
-            Collidable,
-        ))
-        .id();
-
-    let mut spatial_index = world.resource_mut::<SpatialIndex>();
-    spatial_index.update(food, Vec2::new(50.0, 0.0));
-    drop(spatial_index);
+    let mut app = create_fixed_pipeline_app_with_dt(Config::UNLIMITED_SPEED, 50);
+    let ant = spawn_ant_heading_at_food(&mut app);
 
     for _ in 0..200 {
         app.update();
@@ -796,219 +736,1574 @@ fn test_unlimited_speed_no_tunneling_at_low_fps() {
     );
 }
 
-// --- Regression tests: food lifecycle ---
+// --- Swept collision ---
 
 #[test]
-fn test_food_entity_count_preserved() {
-    let mut app = create_test_app();
-
-    let world = app.world_mut();
-    let mut food_ids = Vec::new();
-
-    // Spawn 5 food items in a line
-    let _spatial_index = world.resource_mut::<SpatialIndex>();
-    drop(_spatial_index);
-
-    for i in 0..5 {
-        let x = (i as f32) * 30.0;
-        let food = world
-            .spawn((
-                Food,
-                Transform::from_translation(Vec3::new(x, 0.0, 0.0)),
-                Bounding::from_radius(5.0),
-                Collidable,
-            ))
-            .id();
-        food_ids.push((food, x));
-    }
-
-    let mut spatial_index = world.resource_mut::<SpatialIndex>();
-    for &(food, x) in &food_ids {
-        spatial_index.update(food, Vec2::new(x, 0.0));
-    }
-    drop(spatial_index);
-
-    // Spawn an ant that will collide with the food
-    world.spawn((
-        Ant,
-        Velocity(Vec2::new(1.0, 0.0)),
-        Transform::from_translation(Vec3::new(-10.0, 0.0, 0.0)),
-        Bounding::from_radius(10.0),
-        Collidable,
-    ));
-
-    let initial_food_count = food_ids.len();
+fn test_swept_hit_time_finds_first_contact() {
+    let path = (Vec2::ZERO, Vec2::new(100.0, 0.0));
 
-    // Run enough frames for ant to interact with food
-    for _ in 0..200 {
-        app.update();
-    }
+    let t = collision::swept_hit_time(path.0, path.1, Vec2::new(50.0, 0.0), 10.0);
+    assert!(
+        t.is_some_and(|t| (t - 0.4).abs() < 1e-4),
+        "expected contact 40% of the way along, got {:?}",
+        t
+    );
 
-    // Count remaining food entities using a query
-    let remaining_food: Vec<Entity> = app
-        .world_mut()
-        .query_filtered::<Entity, With<Food>>()
-        .iter(app.world())
-        .collect();
+    let beside = collision::swept_hit_time(path.0, path.1, Vec2::new(50.0, 20.0), 10.0);
+    assert_eq!(
+        beside, None,
+        "a path passing beside the circle should not hit"
+    );
 
-    println!(
-        "Started with {} food, {} remaining after 200 frames",
-        initial_food_count,
-        remaining_food.len()
+    let short = collision::swept_hit_time(path.0, Vec2::new(30.0, 0.0), Vec2::new(50.0, 0.0), 10.0);
+    assert_eq!(
+        short, None,
+        "a path ending before the circle should not hit"
     );
 
+    let inside =
+        collision::swept_hit_time(Vec2::new(45.0, 0.0), path.1, Vec2::new(50.0, 0.0), 10.0);
     assert_eq!(
-        remaining_food.len(),
-        initial_food_count,
-        "Food entities must never be despawned"
+        inside,
+        Some(0.0),
+        "starting inside the circle is a hit at the start"
     );
 }
 
 #[test]
-fn test_dropped_food_position_near_ant() {
-    let mut app = create_test_app();
+fn test_spatial_index_segment_lookup_covers_crossed_cells() {
+    let mut index = SpatialIndex::default();
+    let mut world = World::new();
+    let midway = world.spawn_empty().id();
+    index.update(midway, Vec2::new(100.0, 0.0));
+
+    assert!(index.get_nearby(Vec2::ZERO).is_empty());
+    assert!(index.get_nearby(Vec2::new(200.0, 0.0)).is_empty());
+    assert_eq!(
+        index.get_along_segment(Vec2::ZERO, Vec2::new(200.0, 0.0)),
+        vec![midway]
+    );
+}
 
-    let world = app.world_mut();
+#[derive(Resource)]
+struct JumpDistance(f32);
 
-    // Ant already carrying food1 (manually set up the parent-child relationship)
-    let ant = world
+/// Moves every ant far past anything it could overlap within one tick.
+fn jump_ants(jump: Res<JumpDistance>, mut ants: Query<&mut Transform, With<Ant>>) {
+    for mut transform in &mut ants {
+        transform.translation.x += jump.0;
+    }
+}
+
+fn create_jump_test_app(mode: CollisionMode, jump: f32) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+        .insert_resource(SimulationSettings {
+            speed_multiplier: 1.0,
+            ..default()
+        })
+        .insert_resource(JumpDistance(jump))
+        .add_plugins(SimulationPlugin)
+        .add_plugins(CollisionPlugin::<Food, Ant>::with_mode(mode))
+        .add_systems(FixedUpdate, jump_ants.in_set(SimulationSet::Movement));
+    app
+}
+
+fn spawn_jumping_ant(app: &mut App) -> Entity {
+    app.world_mut()
         .spawn((
             Ant,
             Velocity(Vec2::new(1.0, 0.0)),
-            Transform::from_translation(Vec3::new(100.0, 50.0, 0.0)),
-            Bounding::from_radius(10.0),
+            Transform::from_translation(Vec3::new(0.0, 0.0, Config::ANT_Z_LAYER)),
+            Bounding::from_radius(5.0),
             Collidable,
         ))
-        .id();
+        .id()
+}
 
-    let food1 = world
+fn spawn_food_at(app: &mut App, x: f32) -> Entity {
+    app.world_mut()
         .spawn((
             Food,
-            Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)),
+            Transform::from_translation(Vec3::new(x, 0.0, Config::FOOD_Z_LAYER)),
             Bounding::from_radius(5.0),
+            Collidable,
         ))
-        .id();
+        .id()
+}
 
-    // Attach food1 as child of ant (simulating a previous pickup)
-    world.entity_mut(ant).add_child(food1);
+fn carried_food(app: &App, ant: Entity) -> Vec<Entity> {
+    app.world()
+        .entity(ant)
+        .get::<Children>()
+        .map(|children| children.iter().collect())
+        .unwrap_or_default()
+}
 
-    // Place food2 directly ahead of the ant to force a drop
-    let food2 = world
+#[test]
+fn test_discrete_collision_misses_food_jumped_over() {
+    let mut app = create_jump_test_app(CollisionMode::Discrete, 200.0);
+    let ant = spawn_jumping_ant(&mut app);
+    spawn_food_at(&mut app, 100.0);
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert!(
+        carried_food(&app, ant).is_empty(),
+        "discrete collision only checks end positions, so a 200-unit jump skips the food"
+    );
+}
+
+#[test]
+fn test_swept_collision_catches_food_jumped_over() {
+    let mut app = create_jump_test_app(CollisionMode::Swept, 200.0);
+    let ant = spawn_jumping_ant(&mut app);
+    let food = spawn_food_at(&mut app, 100.0);
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(
+        carried_food(&app, ant),
+        vec![food],
+        "swept collision should pick up food lying on the path"
+    );
+}
+
+#[test]
+fn test_swept_collision_reports_earliest_hit() {
+    let mut app = create_jump_test_app(CollisionMode::Swept, 200.0);
+    let ant = spawn_jumping_ant(&mut app);
+    // Spawned first so it wins any tie broken by entity order
+    let far = spawn_food_at(&mut app, 150.0);
+    let near = spawn_food_at(&mut app, 100.0);
+
+    app.update();
+    app.update();
+
+    let carried = carried_food(&app, ant);
+    assert_eq!(carried, vec![near], "the food reached first should be hit");
+    assert!(!carried.contains(&far));
+}
+
+#[test]
+fn test_swept_path_does_not_cross_the_world_when_wrapping() {
+    let mut app = create_jump_test_app(CollisionMode::Swept, 0.0);
+    app.insert_resource(WorldBounds::new(Vec2::new(200.0, 100.0)))
+        .add_plugins(BoundaryPlugin);
+
+    let ant = app
+        .world_mut()
         .spawn((
-            Food,
-            Transform::from_translation(Vec3::new(105.0, 50.0, 0.0)),
+            Ant,
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(100.0, 0.0, Config::ANT_Z_LAYER)),
             Bounding::from_radius(5.0),
             Collidable,
+            BoundaryWrap,
         ))
         .id();
+    spawn_food_at(&mut app, 0.0);
 
-    let mut spatial_index = world.resource_mut::<SpatialIndex>();
-    spatial_index.update(food2, Vec2::new(105.0, 50.0));
-    drop(spatial_index);
-
-    // Run enough frames for ant to reach food2 and trigger drop
-    for _ in 0..50 {
+    for _ in 0..10 {
         app.update();
     }
 
-    // Check that the drop happened
-    let ant_has_children = app
+    let position = app
         .world()
         .entity(ant)
-        .get::<Children>()
-        .is_some_and(|c| !c.is_empty());
-
-    let food1_pos = app
-        .world()
-        .entity(food1)
         .get::<Transform>()
         .unwrap()
         .translation;
-
-    println!(
-        "Food1 world position after drop: {:?}, ant still carrying: {}",
-        food1_pos, ant_has_children
+    assert!(position.x < 0.0, "ant should have wrapped to the left edge");
+    assert!(
+        carried_food(&app, ant).is_empty(),
+        "wrapping must not sweep the ant through food in the middle of the world"
     );
+}
+
+#[test]
+fn test_swept_collision_skips_a_teleport_across_the_edge() {
+    // Leaves the right edge and reappears on the left without the boundary
+    // system carrying its previous position along
+    let mut app = create_jump_test_app(CollisionMode::Swept, -150.0);
+    app.insert_resource(WorldBounds::new(Vec2::new(200.0, 100.0)));
+
+    let ant = app
+        .world_mut()
+        .spawn((
+            Ant,
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(75.0, 0.0, Config::ANT_Z_LAYER)),
+            Bounding::from_radius(5.0),
+            Collidable,
+        ))
+        .id();
+    spawn_food_at(&mut app, 0.0);
+
+    app.update();
+    app.update();
 
-    // The drop should have happened
     assert!(
-        !ant_has_children,
-        "Ant should have dropped food1 after hitting food2"
+        carried_food(&app, ant).is_empty(),
+        "a jump across the edge must not sweep through food in the middle of the world"
     );
+}
+
+// --- Pheromones ---
+
+fn pheromone_field() -> PheromoneField {
+    PheromoneField::new(&WorldBounds::new(Vec2::new(200.0, 100.0)))
+}
+
+#[test]
+fn test_pheromone_evaporation_decays_scent() {
+    let mut field = pheromone_field();
+    field.deposit(Vec2::ZERO, 1.0);
+
+    field.step(0.5, 0.0, 1.0);
 
-    // Dropped food should be near where the ant was (~100,50), not at origin (0,0)
-    let dist_from_origin = Vec2::new(food1_pos.x, food1_pos.y).length();
     assert!(
-        dist_from_origin > 20.0,
-        "Dropped food should be near ant's drop position, not at origin. Position: {:?}, distance from origin: {:.1}",
-        food1_pos,
-        dist_from_origin
+        (field.total() - (-0.5_f32).exp()).abs() < 1e-5,
+        "half-per-second evaporation should leave e^-0.5 after one second, got {}",
+        field.total()
     );
 }
 
 #[test]
-fn test_dropped_food_regains_collidable() {
-    let mut app = create_test_app();
+fn test_pheromone_diffusion_spreads_without_losing_scent() {
+    let mut field = pheromone_field();
+    field.deposit(Vec2::ZERO, 1.0);
+
+    field.step(0.0, 0.5, 1.0);
+
+    let neighbour = Vec2::new(Config::PHEROMONE_CELL_SIZE, 0.0);
+    assert!(
+        (field.total() - 1.0).abs() < 1e-5,
+        "diffusion alone must conserve scent"
+    );
+    assert!(field.concentration_at(Vec2::ZERO) < 1.0);
+    assert!(field.concentration_at(neighbour) > 0.0);
+}
+
+#[test]
+fn test_pheromone_gradient_points_towards_scent() {
+    let mut field = pheromone_field();
+    field.deposit(Vec2::new(0.0, Config::PHEROMONE_CELL_SIZE), 1.0);
+
+    let gradient = field.gradient_at(Vec2::ZERO);
+
+    assert!(
+        gradient.y > 0.0 && gradient.x.abs() < 1e-6,
+        "got {:?}",
+        gradient
+    );
+}
+
+fn create_pheromone_test_app(settings: SimulationSettings) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+        .insert_resource(settings)
+        .insert_resource(WorldBounds::new(Vec2::new(200.0, 100.0)))
+        .add_plugins(SimulationPlugin)
+        .add_plugins(PheromonePlugin);
+    app
+}
 
+fn spawn_ant_carrying_food(app: &mut App) {
     let world = app.world_mut();
+    let food = world.spawn((Food, Transform::default())).id();
+    world
+        .spawn((
+            Ant,
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(-50.0, 0.0, Config::ANT_Z_LAYER)),
+        ))
+        .add_child(food);
+}
 
-    // Ant already carrying food1
-    let ant = world
+#[test]
+fn test_only_carrying_ants_deposit_pheromone() {
+    let mut app = create_pheromone_test_app(SimulationSettings {
+        pheromone_deposit_rate: 1.0,
+        pheromone_diffusion_rate: 0.0,
+        ..default()
+    });
+    spawn_ant_carrying_food(&mut app);
+    app.world_mut().spawn((
+        Ant,
+        Velocity(Vec2::new(-1.0, 0.0)),
+        Transform::from_translation(Vec3::new(50.0, 40.0, Config::ANT_Z_LAYER)),
+    ));
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let field = app.world().resource::<PheromoneField>();
+    assert!(field.total() > 0.0, "the carrying ant should leave a trail");
+    assert_eq!(
+        field.concentration_at(Vec2::new(50.0, 40.0)),
+        0.0,
+        "the unladen ant should not lay scent"
+    );
+}
+
+#[test]
+fn test_zero_deposit_rate_leaves_field_empty() {
+    let mut app = create_pheromone_test_app(SimulationSettings {
+        pheromone_deposit_rate: 0.0,
+        ..default()
+    });
+    spawn_ant_carrying_food(&mut app);
+    app.update();
+    let stepped = app.world().resource_ref::<PheromoneField>().last_changed();
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let field = app.world().resource_ref::<PheromoneField>();
+    assert!(field.is_empty());
+    assert_eq!(
+        field.last_changed(),
+        stepped,
+        "an empty field with nothing to deposit should not be stepped"
+    );
+}
+
+#[test]
+fn test_unladen_ant_steers_up_the_gradient() {
+    let mut app = create_pheromone_test_app(SimulationSettings {
+        pheromone_evaporation_rate: 0.0,
+        ..default()
+    });
+    app.update();
+    app.world_mut()
+        .resource_mut::<PheromoneField>()
+        .deposit(Vec2::new(0.0, 2.0 * Config::PHEROMONE_CELL_SIZE), 10.0);
+    let ant = app
+        .world_mut()
         .spawn((
             Ant,
             Velocity(Vec2::new(1.0, 0.0)),
-            Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)),
-            Bounding::from_radius(10.0),
-            Collidable,
+            Transform::from_translation(Vec3::new(0.0, 0.0, Config::ANT_Z_LAYER)),
         ))
         .id();
 
-    let food1 = world
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let velocity = app.world().entity(ant).get::<Velocity>().unwrap().0;
+    assert!(
+        velocity.y > 0.0,
+        "ant heading right should turn towards the scent above it, got {:?}",
+        velocity
+    );
+}
+
+// --- Pick-up rules ---
+
+#[test]
+fn test_always_rule_picks_up_and_drops_with_certainty() {
+    let settings = SimulationSettings::default();
+
+    assert_eq!(settings.pickup_rule, PickupRule::Always);
+    assert_eq!(settings.pickup_probability(0.9), 1.0);
+    assert_eq!(settings.drop_probability(0.0), 1.0);
+}
+
+#[test]
+fn test_deneubourg_probabilities_follow_local_density() {
+    let settings = SimulationSettings {
+        pickup_rule: PickupRule::Deneubourg,
+        deneubourg_k1: 0.1,
+        deneubourg_k2: 0.3,
+        ..default()
+    };
+
+    assert_eq!(settings.pickup_probability(0.0), 1.0);
+    assert!((settings.pickup_probability(0.1) - 0.25).abs() < 1e-6);
+    assert_eq!(settings.drop_probability(0.0), 0.0);
+    assert!((settings.drop_probability(0.3) - 0.25).abs() < 1e-6);
+
+    let densities = [0.0, 0.25, 0.5, 1.0];
+    for pair in densities.windows(2) {
+        assert!(settings.pickup_probability(pair[1]) < settings.pickup_probability(pair[0]));
+        assert!(settings.drop_probability(pair[1]) > settings.drop_probability(pair[0]));
+    }
+}
+
+#[test]
+fn test_spatial_index_density_counts_neighbours() {
+    let mut index = SpatialIndex::default();
+    let mut world = World::new();
+    let items: Vec<Entity> = (0..4).map(|_| world.spawn_empty().id()).collect();
+    for (i, &item) in items.iter().enumerate() {
+        index.update(item, Vec2::new(i as f32 * 5.0, 0.0));
+    }
+
+    let expected = 4.0 / Config::FOOD_DENSITY_SATURATION;
+    assert!((index.density_at(Vec2::ZERO, None) - expected).abs() < 1e-6);
+    let without_one = 3.0 / Config::FOOD_DENSITY_SATURATION;
+    assert!((index.density_at(Vec2::ZERO, Some(items[0])) - without_one).abs() < 1e-6);
+    assert_eq!(index.density_at(Vec2::new(500.0, 500.0), None), 0.0);
+}
+
+fn create_pickup_rule_test_app(settings: SimulationSettings) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+        .insert_resource(settings)
+        .insert_resource(SimRng::from_seed(Some(3)))
+        .add_plugins(SimulationPlugin)
+        .add_plugins(CollisionPlugin::<Food, Ant>::new());
+    app
+}
+
+fn spawn_laden_ant_touching_food(app: &mut App) -> Entity {
+    let world = app.world_mut();
+    let carried = world
+        .spawn((Food, Transform::default(), Bounding::from_radius(5.0)))
+        .id();
+    world.spawn((
+        Food,
+        Transform::from_translation(Vec3::new(2.0, 0.0, Config::FOOD_Z_LAYER)),
+        Bounding::from_radius(5.0),
+        Collidable,
+    ));
+    world
         .spawn((
-            Food,
-            Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)),
+            Ant,
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(0.0, 0.0, Config::ANT_Z_LAYER)),
             Bounding::from_radius(5.0),
-            // No Collidable - it's been "picked up"
+            Collidable,
+        ))
+        .add_child(carried)
+        .id()
+}
+
+#[test]
+fn test_deneubourg_drop_declined_where_density_is_low() {
+    let mut app = create_pickup_rule_test_app(SimulationSettings {
+        pickup_rule: PickupRule::Deneubourg,
+        deneubourg_k2: 1000.0,
+        ..default()
+    });
+    let ant = spawn_laden_ant_touching_food(&mut app);
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(
+        carried_food(&app, ant).len(),
+        1,
+        "a near-zero drop probability should keep the food on the ant"
+    );
+    assert!(
+        app.world().entity(ant).get::<Cooldown>().is_some(),
+        "a declined contact should start the cooldown so the ant decides once per encounter"
+    );
+}
+
+#[test]
+fn test_deneubourg_drop_accepted_when_k2_is_zero() {
+    let mut app = create_pickup_rule_test_app(SimulationSettings {
+        pickup_rule: PickupRule::Deneubourg,
+        deneubourg_k2: 0.0,
+        ..default()
+    });
+    let ant = spawn_laden_ant_touching_food(&mut app);
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert!(
+        carried_food(&app, ant).is_empty(),
+        "with k2 = 0 any nearby food makes the drop certain"
+    );
+}
+
+// --- Colonies ---
+
+fn create_colony_test_app() -> App {
+    let mut app = create_pickup_rule_test_app(SimulationSettings {
+        return_to_nest: true,
+        ..default()
+    });
+    app.init_resource::<RecordedBackendEvents>()
+        .add_plugins(ColonyPlugin)
+        .add_systems(
+            FixedUpdate,
+            record_backend_events.after(SimulationSet::Hits),
+        );
+    app.world_mut().spawn(Colony {
+        id: 2,
+        nest: Vec2::ZERO,
+        color: Colors::colony(2),
+    });
+    app
+}
+
+#[test]
+fn test_laden_ant_returns_to_nest_and_drops_food_there() {
+    let mut app = create_colony_test_app();
+    let world = app.world_mut();
+    let carried = world
+        .spawn((Food, Transform::default(), Bounding::from_radius(5.0)))
+        .id();
+    let ant = world
+        .spawn((
+            Ant,
+            ColonyId(2),
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(100.0, 0.0, Config::ANT_Z_LAYER)),
+            Bounding::from_radius(5.0),
+            Collidable,
         ))
+        .add_child(carried)
         .id();
 
-    world.entity_mut(ant).add_child(food1);
+    for _ in 0..120 {
+        app.update();
+    }
 
-    // Place food2 right ahead to trigger drop
-    let food2 = world
+    assert!(
+        carried_food(&app, ant).is_empty(),
+        "the ant should have delivered its food"
+    );
+    let dropped_at = app.world().get::<Transform>(carried).unwrap().translation;
+    assert!(
+        dropped_at.truncate().length() <= Config::NEST_RADIUS,
+        "food should be dropped at the nest, got {dropped_at:?}"
+    );
+    let recorded = &app.world().resource::<RecordedBackendEvents>().0;
+    assert!(
+        recorded
+            .iter()
+            .any(|(_, event)| event.starts_with("FoodDrop") && event.contains("colony_id: 2")),
+        "the drop should be reported with the ant's colony, got {recorded:?}"
+    );
+}
+
+#[test]
+fn test_returning_ant_does_not_drop_on_food_it_passes() {
+    let mut app = create_colony_test_app();
+    let world = app.world_mut();
+    let carried = world
+        .spawn((Food, Transform::default(), Bounding::from_radius(5.0)))
+        .id();
+    world.spawn((
+        Food,
+        Transform::from_translation(Vec3::new(198.0, 0.0, Config::FOOD_Z_LAYER)),
+        Bounding::from_radius(5.0),
+        Collidable,
+    ));
+    let ant = world
         .spawn((
-            Food,
-            Transform::from_translation(Vec3::new(5.0, 0.0, 0.0)),
+            Ant,
+            ColonyId(2),
+            Velocity(Vec2::new(-1.0, 0.0)),
+            Transform::from_translation(Vec3::new(200.0, 0.0, Config::ANT_Z_LAYER)),
             Bounding::from_radius(5.0),
             Collidable,
         ))
+        .add_child(carried)
         .id();
 
-    let mut spatial_index = world.resource_mut::<SpatialIndex>();
-    spatial_index.update(food2, Vec2::new(5.0, 0.0));
-    drop(spatial_index);
+    for _ in 0..3 {
+        app.update();
+    }
 
-    // Run frames to trigger collision and drop
-    for _ in 0..20 {
+    assert_eq!(
+        carried_food(&app, ant).len(),
+        1,
+        "a returning ant keeps its food until it reaches the nest"
+    );
+}
+
+// --- Clustering analytics ---
+
+#[test]
+fn test_clustering_stats_cover_loose_food_every_n_frames() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(SimulationPlugin)
+        .add_plugins(AnalyticsPlugin);
+    let world = app.world_mut();
+    world.spawn((Food, Transform::from_xyz(0.0, 0.0, Config::FOOD_Z_LAYER)));
+    let moved = world
+        .spawn((Food, Transform::from_xyz(3.0, 4.0, Config::FOOD_Z_LAYER)))
+        .id();
+    let carried = world.spawn((Food, Transform::default())).id();
+    world
+        .spawn((Ant, Transform::from_xyz(500.0, 0.0, Config::ANT_Z_LAYER)))
+        .add_child(carried);
+
+    app.update();
+    let stats = app.world().resource::<ClusteringStats>().clone();
+    assert_eq!(
+        stats.summary.loose_food_count, 2,
+        "carried food is not loose"
+    );
+    assert_eq!(stats.summary.occupied_cell_count, 1);
+    assert!((stats.summary.nearest_neighbor_mean_distance - 5.0).abs() < 1e-6);
+
+    app.world_mut()
+        .get_mut::<Transform>(moved)
+        .unwrap()
+        .translation
+        .x = 300.0;
+    for _ in 1..Config::ANALYTICS_UPDATE_FRAMES {
         app.update();
     }
+    assert_eq!(
+        app.world()
+            .resource::<ClusteringStats>()
+            .summary
+            .occupied_cell_count,
+        1,
+        "stats should only refresh every ANALYTICS_UPDATE_FRAMES frames"
+    );
 
-    let ant_carrying = app
-        .world()
-        .entity(ant)
-        .get::<Children>()
-        .is_some_and(|c| !c.is_empty());
+    app.update();
+    assert_eq!(
+        app.world()
+            .resource::<ClusteringStats>()
+            .summary
+            .occupied_cell_count,
+        2
+    );
+}
 
-    let food1_collidable = app.world().entity(food1).get::<Collidable>().is_some();
+fn summary_with_loose_food(count: usize) -> ClusteringSummary {
+    ClusteringSummary {
+        loose_food_count: count,
+        ..default()
+    }
+}
 
-    println!(
-        "After drop: ant carrying={}, food1 collidable={}",
-        ant_carrying, food1_collidable
+#[test]
+fn test_clustering_history_keeps_last_samples_in_tick_order() {
+    let mut history = ClusteringHistory::default();
+    for tick in 0..Config::ANALYTICS_HISTORY_LEN as u64 + 5 {
+        history.push(tick * 10, summary_with_loose_food(tick as usize));
+    }
+    assert_eq!(history.len(), Config::ANALYTICS_HISTORY_LEN);
+    assert_eq!(history.samples().next().unwrap().0, 50, "oldest dropped");
+
+    // A refresh without the sim ticking replaces the last sample
+    let last_tick = history.samples().last().unwrap().0;
+    history.push(last_tick, summary_with_loose_food(0));
+    assert_eq!(history.len(), Config::ANALYTICS_HISTORY_LEN);
+    assert_eq!(
+        history.range(ClusteringMetric::LooseFood),
+        Some((0.0, Config::ANALYTICS_HISTORY_LEN as f64 + 3.0))
     );
+}
 
-    assert!(!ant_carrying, "Ant should have dropped food after hitting food2");
+#[test]
+fn test_clustering_history_normalizes_by_tick_and_range() {
+    let mut history = ClusteringHistory::default();
+    assert!(history.normalized(ClusteringMetric::LooseFood).is_empty());
+
+    history.push(0, summary_with_loose_food(10));
+    history.push(30, summary_with_loose_food(20));
+    history.push(120, summary_with_loose_food(30));
+
+    let points = history.normalized(ClusteringMetric::LooseFood);
+    assert_eq!(
+        points,
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.25, 0.5),
+            Vec2::new(1.0, 1.0)
+        ]
+    );
     assert!(
-        food1_collidable,
-        "Dropped food must regain Collidable component"
+        history
+            .normalized(ClusteringMetric::OccupiedCells)
+            .iter()
+            .all(|point| point.y == 0.5),
+        "a flat series sits mid-chart"
+    );
+}
+
+// --- Regression tests: food lifecycle ---
+
+#[test]
+fn test_food_entity_count_preserved() {
+    let mut app = create_test_app();
+
+    let world = app.world_mut();
+    let mut food_ids = Vec::new();
+
+    // Spawn 5 food items in a line
+    let _spatial_index = world.resource_mut::<SpatialIndex>();
+    drop(_spatial_index);
+
+    for i in 0..5 {
+        let x = (i as f32) * 30.0;
+        let food = world
+            .spawn((
+                Food,
+                Transform::from_translation(Vec3::new(x, 0.0, 0.0)),
+                Bounding::from_radius(5.0),
+                Collidable,
+            ))
+            .id();
+        food_ids.push((food, x));
+    }
+
+    let mut spatial_index = world.resource_mut::<SpatialIndex>();
+    for &(food, x) in &food_ids {
+        spatial_index.update(food, Vec2::new(x, 0.0));
+    }
+    drop(spatial_index);
+
+    // Spawn an ant that will collide with the food
+    world.spawn((
+        Ant,
+        Velocity(Vec2::new(1.0, 0.0)),
+        Transform::from_translation(Vec3::new(-10.0, 0.0, 0.0)),
+        Bounding::from_radius(10.0),
+        Collidable,
+    ));
+
+    let initial_food_count = food_ids.len();
+
+    // Run enough frames for ant to interact with food
+    for _ in 0..200 {
+        app.update();
+    }
+
+    // Count remaining food entities using a query
+    let remaining_food: Vec<Entity> = app
+        .world_mut()
+        .query_filtered::<Entity, With<Food>>()
+        .iter(app.world())
+        .collect();
+
+    println!(
+        "Started with {} food, {} remaining after 200 frames",
+        initial_food_count,
+        remaining_food.len()
+    );
+
+    assert_eq!(
+        remaining_food.len(),
+        initial_food_count,
+        "Food entities must never be despawned"
+    );
+}
+
+#[test]
+fn test_dropped_food_position_near_ant() {
+    let mut app = create_test_app();
+
+    let world = app.world_mut();
+
+    // Ant already carrying food1 (manually set up the parent-child relationship)
+    let ant = world
+        .spawn((
+            Ant,
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(100.0, 50.0, 0.0)),
+            Bounding::from_radius(10.0),
+            Collidable,
+        ))
+        .id();
+
+    let food1 = world
+        .spawn((
+            Food,
+            Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)),
+            Bounding::from_radius(5.0),
+        ))
+        .id();
+
+    // Attach food1 as child of ant (simulating a previous pickup)
+    world.entity_mut(ant).add_child(food1);
+
+    // Place food2 directly ahead of the ant to force a drop
+    let food2 = world
+        .spawn((
+            Food,
+            Transform::from_translation(Vec3::new(105.0, 50.0, 0.0)),
+            Bounding::from_radius(5.0),
+            Collidable,
+        ))
+        .id();
+
+    let mut spatial_index = world.resource_mut::<SpatialIndex>();
+    spatial_index.update(food2, Vec2::new(105.0, 50.0));
+    drop(spatial_index);
+
+    // Run enough frames for ant to reach food2 and trigger drop
+    for _ in 0..50 {
+        app.update();
+    }
+
+    // Check that the drop happened
+    let ant_has_children = app
+        .world()
+        .entity(ant)
+        .get::<Children>()
+        .is_some_and(|c| !c.is_empty());
+
+    let food1_pos = app
+        .world()
+        .entity(food1)
+        .get::<Transform>()
+        .unwrap()
+        .translation;
+
+    println!(
+        "Food1 world position after drop: {:?}, ant still carrying: {}",
+        food1_pos, ant_has_children
+    );
+
+    // The drop should have happened
+    assert!(
+        !ant_has_children,
+        "Ant should have dropped food1 after hitting food2"
+    );
+
+    // Dropped food should be near where the ant was (~100,50), not at origin (0,0)
+    let dist_from_origin = Vec2::new(food1_pos.x, food1_pos.y).length();
+    assert!(
+        dist_from_origin > 20.0,
+        "Dropped food should be near ant's drop position, not at origin. Position: {:?}, distance from origin: {:.1}",
+        food1_pos,
+        dist_from_origin
+    );
+}
+
+#[test]
+fn test_dropped_food_regains_collidable() {
+    let mut app = create_test_app();
+
+    let world = app.world_mut();
+
+    // Ant already carrying food1
+    let ant = world
+        .spawn((
+            Ant,
+            Velocity(Vec2::new(1.0, 0.0)),
+            Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)),
+            Bounding::from_radius(10.0),
+            Collidable,
+        ))
+        .id();
+
+    let food1 = world
+        .spawn((
+            Food,
+            Transform::from_translation(Vec3::new(0.0, 0.0, 0.0)),
+            Bounding::from_radius(5.0),
+            // No Collidable - it's been "picked up"
+        ))
+        .id();
+
+    world.entity_mut(ant).add_child(food1);
+
+    // Place food2 right ahead to trigger drop
+    let food2 = world
+        .spawn((
+            Food,
+            Transform::from_translation(Vec3::new(5.0, 0.0, 0.0)),
+            Bounding::from_radius(5.0),
+            Collidable,
+        ))
+        .id();
+
+    let mut spatial_index = world.resource_mut::<SpatialIndex>();
+    spatial_index.update(food2, Vec2::new(5.0, 0.0));
+    drop(spatial_index);
+
+    // Run frames to trigger collision and drop
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let ant_carrying = app
+        .world()
+        .entity(ant)
+        .get::<Children>()
+        .is_some_and(|c| !c.is_empty());
+
+    let food1_collidable = app.world().entity(food1).get::<Collidable>().is_some();
+
+    println!(
+        "After drop: ant carrying={}, food1 collidable={}",
+        ant_carrying, food1_collidable
+    );
+
+    assert!(!ant_carrying, "Ant should have dropped food after hitting food2");
+    assert!(
+        food1_collidable,
+        "Dropped food must regain Collidable component"
+    );
+}
+
+// --- World bounds ---
+
+#[test]
+fn test_boundary_wrap_uses_world_bounds() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(WorldBounds::new(Vec2::new(200.0, 100.0)))
+        .add_plugins(BoundaryPlugin);
+
+    let ant = app
+        .world_mut()
+        .spawn((
+            Ant,
+            Transform::from_translation(Vec3::new(111.0, 0.0, 0.0)),
+            Bounding::from_radius(10.0),
+            BoundaryWrap,
+        ))
+        .id();
+
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let position = app
+        .world()
+        .entity(ant)
+        .get::<Transform>()
+        .unwrap()
+        .translation;
+    assert_eq!(
+        position.x, -110.0,
+        "ant leaving the right edge of a 200-wide world should wrap to the left edge"
+    );
+}
+
+// --- Pause and stepping ---
+
+fn create_control_test_app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+        .insert_resource(SimulationSettings {
+            speed_multiplier: 1.0,
+            ..default()
+        })
+        .add_plugins(SimulationPlugin);
+    let ant = spawn_rightward_ant(&mut app);
+    (app, ant)
+}
+
+fn tick_and_ant_x(app: &App, ant: Entity) -> (u64, f32) {
+    (
+        app.world().resource::<SimTick>().0,
+        app.world().get::<Transform>(ant).unwrap().translation.x,
+    )
+}
+
+#[test]
+fn test_paused_simulation_does_not_advance() {
+    let (mut app, ant) = create_control_test_app();
+    app.update();
+    app.world_mut().resource_mut::<SimControl>().pause();
+    let before = tick_and_ant_x(&app, ant);
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(tick_and_ant_x(&app, ant), before);
+
+    app.world_mut().resource_mut::<SimControl>().resume();
+    app.update();
+    let after = tick_and_ant_x(&app, ant);
+    assert_eq!(after.0, before.0 + 1);
+    assert!(after.1 > before.1, "ant should move again once resumed");
+}
+
+#[test]
+fn test_step_and_run_ticks_advance_exactly_then_pause() {
+    let (mut app, ant) = create_control_test_app();
+    app.world_mut().resource_mut::<SimControl>().pause();
+    app.update();
+    let (start, _) = tick_and_ant_x(&app, ant);
+
+    app.world_mut().resource_mut::<SimControl>().step();
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(tick_and_ant_x(&app, ant).0, start + 1);
+    assert!(app.world().resource::<SimControl>().is_paused());
+
+    app.world_mut().resource_mut::<SimControl>().run_ticks(3);
+    app.update();
+    assert_eq!(
+        app.world().resource::<SimControl>().mode(),
+        RunMode::Ticks(2)
+    );
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(tick_and_ant_x(&app, ant).0, start + 4);
+    assert!(app.world().resource::<SimControl>().is_paused());
+}
+
+#[test]
+fn test_reset_clears_the_world_and_reseeds_the_simulation() {
+    use rand::Rng;
+
+    let (mut app, ant) = create_control_test_app();
+    let world = app.world_mut();
+    let carried = world.spawn((Food, Transform::default())).id();
+    world.entity_mut(ant).add_child(carried);
+    world.spawn((Food, Transform::from_xyz(100.0, 0.0, 0.0)));
+    world.spawn(Colony {
+        id: 0,
+        nest: Vec2::ZERO,
+        color: Color::WHITE,
+    });
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let mut control = app.world_mut().resource_mut::<SimControl>();
+    control.pause();
+    control.request_reset(42);
+    app.update();
+
+    let world = app.world_mut();
+    let leftovers = world
+        .query_filtered::<(), Or<(With<Ant>, With<Food>, With<Colony>)>>()
+        .iter(world)
+        .count();
+    assert_eq!(leftovers, 0, "the old world should be gone");
+    assert_eq!(world.resource::<SimTick>().0, 0);
+    assert_eq!(world.resource::<SimControl>().reset_requested(), None);
+    let resets: Vec<SimReset> = world.resource_mut::<Messages<SimReset>>().drain().collect();
+    assert_eq!(resets, [SimReset { seed: 42 }]);
+    assert_eq!(
+        world.resource_mut::<SimRng>().random::<u64>(),
+        SimRng::from_seed(Some(42)).random::<u64>()
+    );
+}
+
+// --- Interactive edits ---
+
+#[derive(Resource, Default)]
+struct EmittedBackendEvents(Vec<BackendSimEvent>);
+
+fn collect_emitted_backend_events(
+    mut events: MessageReader<BackendSimEvent>,
+    mut emitted: ResMut<EmittedBackendEvents>,
+) {
+    emitted.0.extend(events.read().cloned());
+}
+
+fn create_edit_test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<SpatialIndex>()
+        .init_resource::<SimRng>()
+        .init_resource::<SimulationSettings>()
+        .init_resource::<an_gatherers::net::BackendFoodSlots>()
+        .init_resource::<EmittedBackendEvents>()
+        .add_message::<EditCommand>()
+        .add_message::<BackendSimEvent>()
+        .add_systems(
+            Update,
+            (
+                an_gatherers::interaction::apply_edit_commands,
+                collect_emitted_backend_events,
+            )
+                .chain(),
+        );
+    app
+}
+
+fn spawn_loose_food(app: &mut App, position: Vec2) -> Entity {
+    let food = app
+        .world_mut()
+        .spawn((
+            Food,
+            Transform::from_translation(position.extend(Config::FOOD_Z_LAYER)),
+            Bounding::from_radius(5.0),
+            Collidable,
+        ))
+        .id();
+    app.world_mut()
+        .resource_mut::<SpatialIndex>()
+        .update(food, position);
+    food
+}
+
+#[test]
+fn test_placed_food_is_collidable_indexed_and_announced() {
+    let mut app = create_edit_test_app();
+    spawn_loose_food(&mut app, Vec2::new(-100.0, 0.0));
+    spawn_loose_food(&mut app, Vec2::new(100.0, 0.0));
+
+    let position = Vec2::new(40.0, 30.0);
+    app.world_mut()
+        .write_message(EditCommand::PlaceFood(position));
+    app.update();
+
+    let world = app.world_mut();
+    let placed: Vec<Entity> = world
+        .query_filtered::<(Entity, &Transform), (With<Food>, With<Collidable>, With<Bounding>)>()
+        .iter(world)
+        .filter(|(_, transform)| transform.translation.truncate() == position)
+        .map(|(entity, _)| entity)
+        .collect();
+    assert_eq!(placed.len(), 1, "placed food should be collidable");
+    assert!(
+        world
+            .resource::<SpatialIndex>()
+            .get_nearby(position)
+            .contains(&placed[0])
+    );
+
+    let events = &app.world().resource::<EmittedBackendEvents>().0;
+    assert!(
+        matches!(
+            events.as_slice(),
+            [BackendSimEvent::FoodSpawn { food_id: 2, .. }]
+        ),
+        "new food should take the slot after the startup food, got {events:?}"
+    );
+}
+
+#[test]
+fn test_spawned_ant_heads_in_a_random_direction() {
+    let mut app = create_edit_test_app();
+    app.world_mut()
+        .write_message(EditCommand::SpawnAnt(Vec2::new(10.0, 20.0)));
+    app.update();
+
+    let world = app.world_mut();
+    let (transform, velocity) = world
+        .query_filtered::<(&Transform, &Velocity), (With<Ant>, With<Collidable>)>()
+        .single(world)
+        .expect("one ant should be spawned");
+    assert_eq!(transform.translation.truncate(), Vec2::new(10.0, 20.0));
+    assert!((velocity.0.length() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_spawning_an_ant_leaves_the_sim_rng_untouched() {
+    use rand::Rng;
+
+    let mut app = create_edit_test_app();
+    app.insert_resource(SimRng::from_seed(Some(7)));
+    app.world_mut()
+        .write_message(EditCommand::SpawnAnt(Vec2::new(10.0, 20.0)));
+    app.update();
+
+    let mut expected = SimRng::from_seed(Some(7));
+    assert_eq!(
+        app.world_mut().resource_mut::<SimRng>().random::<u64>(),
+        expected.random::<u64>(),
+        "a click should not shift the seeded sim's random sequence"
+    );
+}
+
+#[test]
+fn test_spawned_ant_joins_the_colony_of_the_nearest_nest() {
+    let mut app = create_edit_test_app();
+    for (id, nest) in [(0, Vec2::new(-200.0, 0.0)), (1, Vec2::new(200.0, 0.0))] {
+        app.world_mut().spawn(Colony {
+            id,
+            nest,
+            color: Colors::colony(id),
+        });
+    }
+    app.world_mut()
+        .write_message(EditCommand::SpawnAnt(Vec2::new(150.0, 50.0)));
+    app.update();
+
+    let world = app.world_mut();
+    let colony = world
+        .query_filtered::<&ColonyId, With<Ant>>()
+        .single(world)
+        .expect("one ant should be spawned");
+    assert_eq!(colony.0, 1);
+}
+
+#[test]
+fn test_erase_removes_food_and_ants_inside_the_rect() {
+    let mut app = create_edit_test_app();
+    let inside = spawn_loose_food(&mut app, Vec2::new(10.0, 10.0));
+    let outside = spawn_loose_food(&mut app, Vec2::new(200.0, 10.0));
+    let carried = app.world_mut().spawn((Food, Transform::default())).id();
+    let ant = app
+        .world_mut()
+        .spawn((Ant, Transform::from_xyz(-10.0, -10.0, Config::ANT_Z_LAYER)))
+        .add_child(carried)
+        .id();
+    app.update();
+
+    app.world_mut()
+        .write_message(EditCommand::Erase(Rect::new(-50.0, -50.0, 50.0, 50.0)));
+    app.update();
+
+    let world = app.world();
+    for entity in [inside, carried, ant] {
+        assert!(
+            world.get_entity(entity).is_err(),
+            "{entity} should be erased"
+        );
+    }
+    assert!(world.get_entity(outside).is_ok());
+    assert!(
+        !world
+            .resource::<SpatialIndex>()
+            .get_nearby(Vec2::new(10.0, 10.0))
+            .contains(&inside)
+    );
+
+    let removed: Vec<usize> = world
+        .resource::<EmittedBackendEvents>()
+        .0
+        .iter()
+        .filter_map(|event| match event {
+            BackendSimEvent::FoodRemove { food_id } => Some(*food_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(removed.len(), 2, "loose and carried food are both removed");
+}
+
+// --- Camera ---
+
+#[test]
+fn test_zoom_keeps_the_anchor_under_the_cursor() {
+    let anchor = Vec2::new(100.0, -40.0);
+    let (center, scale) = an_gatherers::camera::zoom_about(Vec2::ZERO, 1.0, anchor, 0.5);
+    assert_eq!(scale, 0.5);
+    assert_eq!(center, Vec2::new(50.0, -20.0));
+    // The anchor is the same number of screen units from the centre
+    assert_eq!((anchor - center) / scale, anchor);
+
+    let (_, scale) = an_gatherers::camera::zoom_about(center, scale, anchor, 1000.0);
+    assert_eq!(scale, Config::MAX_ZOOM_SCALE);
+    let (_, scale) = an_gatherers::camera::zoom_about(center, scale, anchor, 0.0);
+    assert_eq!(scale, Config::MIN_ZOOM_SCALE);
+}
+
+#[test]
+fn test_fit_world_key_resets_pan_and_zoom() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<WorldBounds>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<bevy::input::mouse::AccumulatedMouseScroll>()
+        .add_plugins(CameraPlugin);
+    app.update();
+
+    let world = app.world_mut();
+    let (mut transform, mut projection) = world
+        .query_filtered::<(&mut Transform, &mut Projection), With<WorldCamera>>()
+        .single_mut(world)
+        .expect("the world camera should be spawned");
+    transform.translation = Vec3::new(300.0, -200.0, 0.0);
+    let Projection::Orthographic(orthographic) = projection.as_mut() else {
+        panic!("world camera should be orthographic");
+    };
+    orthographic.scale = 0.25;
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyF);
+    app.update();
+
+    let world = app.world_mut();
+    let (transform, projection) = world
+        .query_filtered::<(&Transform, &Projection), With<WorldCamera>>()
+        .single(world)
+        .unwrap();
+    assert_eq!(transform.translation.truncate(), Vec2::ZERO);
+    assert!(matches!(projection, Projection::Orthographic(o) if o.scale == 1.0));
+}
+
+// --- Heatmap ---
+
+#[test]
+fn test_heatmap_counts_food_per_grid_cell() {
+    let counts = an_gatherers::heatmap::food_counts_per_cell(
+        [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(49.0, 49.0),
+            Vec2::new(50.0, 0.0),
+            Vec2::new(-1.0, -1.0),
+        ],
+        50.0,
+    );
+    assert_eq!(counts.len(), 3);
+    assert_eq!(counts[&IVec2::new(0, 0)], 2);
+    assert_eq!(counts[&IVec2::new(1, 0)], 1);
+    assert_eq!(counts[&IVec2::new(-1, -1)], 1);
+}
+
+fn create_heatmap_test_app(settings: SimulationSettings) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(settings)
+        .init_resource::<ButtonInput<KeyCode>>()
+        .add_plugins(HeatmapPlugin);
+    for position in [
+        Vec2::new(10.0, 10.0),
+        Vec2::new(20.0, 20.0),
+        Vec2::new(300.0, 0.0),
+    ] {
+        app.world_mut()
+            .spawn((Food, Transform::from_translation(position.extend(0.0))));
+    }
+    app
+}
+
+#[test]
+fn test_heatmap_overlay_draws_a_cell_per_occupied_cell_when_visible() {
+    let mut app = create_heatmap_test_app(SimulationSettings::default());
+
+    let cell_count = |app: &mut App| {
+        let world = app.world_mut();
+        world
+            .query_filtered::<Option<&Children>, With<an_gatherers::heatmap::HeatmapRoot>>()
+            .single(world)
+            .unwrap()
+            .map_or(0, |children| children.len())
+    };
+
+    app.update();
+    assert_eq!(cell_count(&mut app), 0, "hidden overlay draws nothing");
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyH);
+    app.update();
+    assert!(app.world().resource::<HeatmapOverlay>().visible);
+    assert_eq!(cell_count(&mut app), 2);
+}
+
+#[test]
+fn test_heatmap_cells_stay_visible_at_tiny_cell_sizes() {
+    let mut app = create_heatmap_test_app(SimulationSettings {
+        heatmap_cell_size: 1.0,
+        ..default()
+    });
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyH);
+    app.update();
+
+    let world = app.world_mut();
+    let sizes: Vec<Vec2> = world
+        .query::<&Sprite>()
+        .iter(world)
+        .filter_map(|sprite| sprite.custom_size)
+        .collect();
+    assert_eq!(sizes.len(), 3);
+    assert!(
+        sizes.iter().all(|size| size.min_element() > 0.0),
+        "every cell sprite needs a positive size, got {sizes:?}"
+    );
+}
+
+// --- Inspector ---
+
+#[test]
+fn test_entity_at_picks_the_nearest_hit() {
+    let mut world = World::new();
+    let near = world.spawn_empty().id();
+    let far = world.spawn_empty().id();
+    let missed = world.spawn_empty().id();
+    let candidates = [
+        (far, Vec2::new(8.0, 0.0), 10.0),
+        (near, Vec2::new(-3.0, 0.0), 5.0),
+        (missed, Vec2::new(1.0, 20.0), 5.0),
+    ];
+
+    assert_eq!(
+        an_gatherers::inspector::entity_at(Vec2::ZERO, candidates),
+        Some(near)
+    );
+    assert_eq!(
+        an_gatherers::inspector::entity_at(Vec2::new(0.0, 100.0), candidates),
+        None
+    );
+}
+
+#[test]
+fn test_gather_counts_track_drops() {
+    let mut app = create_pickup_rule_test_app(SimulationSettings::default());
+    let ant = spawn_laden_ant_touching_food(&mut app);
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert!(carried_food(&app, ant).is_empty());
+    let counts = app.world().entity(ant).get::<GatherCounts>().unwrap();
+    assert_eq!(
+        *counts,
+        GatherCounts {
+            pickups: 0,
+            drops: 1
+        }
+    );
+}
+
+#[test]
+fn test_inspector_shows_the_selected_ant_until_closed() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<ButtonInput<KeyCode>>()
+        .add_plugins(InspectorPlugin);
+    let ant = app
+        .world_mut()
+        .spawn((
+            Ant,
+            Velocity(Vec2::new(0.5, -1.0)),
+            Transform::from_xyz(12.0, 34.0, 0.0),
+            GatherCounts {
+                pickups: 3,
+                drops: 2,
+            },
+        ))
+        .id();
+    app.insert_resource(Inspected(Some(ant)));
+
+    let panel = |app: &mut App| {
+        let world = app.world_mut();
+        let visibility = *world
+            .query_filtered::<&Visibility, With<an_gatherers::inspector::InspectorPanel>>()
+            .single(world)
+            .unwrap();
+        let text = world
+            .query_filtered::<&Text, With<an_gatherers::inspector::InspectorLabel>>()
+            .single(world)
+            .unwrap()
+            .0
+            .clone();
+        (visibility, text)
+    };
+
+    app.update();
+    app.update();
+    let (visibility, text) = panel(&mut app);
+    assert_eq!(visibility, Visibility::Visible);
+    assert!(
+        text.contains(&format!("backend id: {}", ant.to_bits())),
+        "{text}"
+    );
+    assert!(text.contains("velocity: (0.50, -1.00)"), "{text}");
+    assert!(text.contains("carrying: nothing"), "{text}");
+    assert!(text.contains("pickups: 3  drops: 2"), "{text}");
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::Escape);
+    app.update();
+    assert_eq!(app.world().resource::<Inspected>().0, None);
+    assert_eq!(panel(&mut app).0, Visibility::Hidden);
+}
+
+// --- Determinism ---
+
+#[derive(Resource, Default)]
+struct RecordedBackendEvents(Vec<(u64, String)>);
+
+fn record_backend_events(
+    tick: Res<SimTick>,
+    mut events: MessageReader<BackendSimEvent>,
+    mut recorded: ResMut<RecordedBackendEvents>,
+) {
+    for event in events.read() {
+        recorded.0.push((tick.0, format!("{event:?}")));
+    }
+}
+
+/// Runs the full fixed-timestep pipeline from a seeded layout until `ticks`
+/// ticks have passed, running `ticks_per_frame` ticks per rendered frame.
+fn run_seeded_simulation(seed: u64, ticks: u64, ticks_per_frame: u32) -> Vec<(u64, String)> {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(ticks_per_frame))
+        .insert_resource(SimulationSettings::default())
+        .insert_resource(SimRng::from_seed(Some(seed)))
+        .init_resource::<RecordedBackendEvents>()
+        .add_message::<BackendSimEvent>()
+        .add_plugins(SimulationPlugin)
+        .add_plugins(BoundaryPlugin)
+        .add_plugins(CollisionPlugin::<Food, Ant>::new())
+        .add_systems(
+            FixedUpdate,
+            record_backend_events.after(SimulationSet::Hits),
+        );
+
+    let settings = SimulationSettings::default();
+    let bounds = *app.world().resource::<WorldBounds>();
+    let layout = generate_spawn_layout(bounds.size(), &settings, Some(seed));
+    let world = app.world_mut();
+    for ant in &layout.ants {
+        world.spawn((
+            Ant,
+            Transform::from_translation(ant.position),
+            Velocity::from(ant.direction),
+            Bounding::from_radius(settings.collision_radius()),
+            Collidable,
+            BoundaryWrap,
+        ));
+    }
+    for position in &layout.food_positions {
+        world.spawn((
+            Food,
+            Transform::from_translation(position.extend(Config::FOOD_Z_LAYER)),
+            Bounding::from_radius(settings.collision_radius()),
+            Collidable,
+        ));
+    }
+
+    while app.world().resource::<SimTick>().0 < ticks {
+        app.update();
+    }
+
+    let recorded = &app.world().resource::<RecordedBackendEvents>().0;
+    recorded
+        .iter()
+        .filter(|(tick, _)| *tick <= ticks)
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_same_seed_and_tick_count_reproduce_event_stream() {
+    let first = run_seeded_simulation(7, 600, 1);
+    let second = run_seeded_simulation(7, 600, 1);
+
+    assert!(
+        !first.is_empty(),
+        "expected pickups or drops within 600 ticks to make the comparison meaningful"
+    );
+    assert_eq!(first, second, "same seed and tick count should give the same event stream");
+}
+
+#[test]
+fn test_event_stream_does_not_depend_on_frame_pacing() {
+    let one_tick_per_frame = run_seeded_simulation(11, 600, 1);
+    let three_ticks_per_frame = run_seeded_simulation(11, 600, 3);
+
+    assert!(!one_tick_per_frame.is_empty());
+    assert_eq!(
+        one_tick_per_frame, three_ticks_per_frame,
+        "how ticks are grouped into frames must not change the simulation"
     );
 }